@@ -0,0 +1,53 @@
+//! Правила переключения по окну, получившему фокус
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use super::pattern::WindowPattern;
+use crate::virtual_desktop::api::WindowInfo;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FocusRule {
+    pub id: u32,
+    pub enabled: bool,
+    pub desktop_id: i32,
+    pub include: Vec<WindowPattern>,
+    #[serde(default)]
+    pub exclude: Vec<WindowPattern>,
+}
+
+impl FocusRule {
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        self.enabled
+            && self.include.iter().any(|p| p.matches(window))
+            && !self.exclude.iter().any(|p| p.matches(window))
+    }
+}
+
+/// Выбирает стол для окна в фокусе и не даёт переключаться чаще `cooldown`
+#[derive(Debug, Default)]
+pub struct FocusTracker {
+    last_switch: Option<Instant>,
+}
+
+impl FocusTracker {
+    pub fn evaluate(
+        &self,
+        window: &WindowInfo,
+        rules: &[FocusRule],
+        cooldown: Duration,
+        now: Instant,
+    ) -> Option<i32> {
+        if let Some(last) = self.last_switch {
+            if now.duration_since(last) < cooldown {
+                return None;
+            }
+        }
+
+        rules.iter().find(|r| r.matches(window)).map(|r| r.desktop_id)
+    }
+
+    pub fn record_switch(&mut self, now: Instant) {
+        self.last_switch = Some(now);
+    }
+}
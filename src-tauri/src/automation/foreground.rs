@@ -0,0 +1,114 @@
+//! Отслеживание смены активного окна (WinEvent hook)
+
+use tauri::AppHandle;
+
+#[cfg(windows)]
+const FOCUS_SETTLE_MS: u64 = 400;
+
+#[cfg(windows)]
+pub fn start_focus_watcher(app_handle: AppHandle) {
+    hook::start(app_handle);
+}
+
+#[cfg(not(windows))]
+pub fn start_focus_watcher(_app_handle: AppHandle) {}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Windows event hook
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod hook {
+    use super::FOCUS_SETTLE_MS;
+    use crate::automation::focus::FocusTracker;
+    use crate::automation::settings::current_settings;
+    use crate::virtual_desktop::api::get_window_info;
+    use std::sync::mpsc::{channel, Receiver, Sender};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::{Duration, Instant};
+    use tauri::AppHandle;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetMessageW, EVENT_SYSTEM_FOREGROUND, MSG, WINEVENT_OUTOFCONTEXT,
+    };
+
+    static FOREGROUND_TX: OnceLock<Mutex<Sender<isize>>> = OnceLock::new();
+
+    pub fn start(app_handle: AppHandle) {
+        let (tx, rx) = channel();
+        if FOREGROUND_TX.set(Mutex::new(tx)).is_err() {
+            return;
+        }
+
+        std::thread::spawn(|| unsafe {
+            let hook = SetWinEventHook(
+                EVENT_SYSTEM_FOREGROUND,
+                EVENT_SYSTEM_FOREGROUND,
+                None,
+                Some(win_event_proc),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            );
+
+            if !hook.is_invalid() {
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, None, 0, 0).0 > 0 {}
+                let _ = UnhookWinEvent(hook);
+            }
+        });
+
+        std::thread::spawn(move || focus_loop(app_handle, rx));
+    }
+
+    unsafe extern "system" fn win_event_proc(
+        _hook: HWINEVENTHOOK,
+        _event: u32,
+        hwnd: HWND,
+        _id_object: i32,
+        _id_child: i32,
+        _event_thread: u32,
+        _event_time: u32,
+    ) {
+        if let Some(tx) = FOREGROUND_TX.get() {
+            if let Ok(tx) = tx.lock() {
+                let _ = tx.send(hwnd.0 as isize);
+            }
+        }
+    }
+
+    fn focus_loop(app_handle: AppHandle, rx: Receiver<isize>) {
+        let mut tracker = FocusTracker::default();
+
+        while let Ok(mut hwnd) = rx.recv() {
+            // Ждём, пока фокус успокоится: Alt+Tab перебирает окна одно за другим
+            while let Ok(next) = rx.recv_timeout(Duration::from_millis(FOCUS_SETTLE_MS)) {
+                hwnd = next;
+            }
+
+            let settings = current_settings();
+            if settings.paused || settings.focus_rules.is_empty() {
+                continue;
+            }
+
+            let Some(window) = get_window_info(hwnd) else {
+                continue;
+            };
+
+            // Собственные окна Kimi никогда не переключают стол
+            if window.process_id == std::process::id() {
+                continue;
+            }
+
+            let cooldown = Duration::from_secs(settings.focus_cooldown_secs);
+            let target = tracker.evaluate(&window, &settings.focus_rules, cooldown, Instant::now());
+
+            if let Some(desktop_id) = target {
+                if crate::automation::apply_switch(&app_handle, desktop_id, "focus") {
+                    tracker.record_switch(Instant::now());
+                }
+            }
+        }
+    }
+}
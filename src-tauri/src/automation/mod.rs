@@ -0,0 +1,54 @@
+//! Автоматизация: переключение столов (расписание, простой, фокус окна)
+//! и размещение окон по правилам
+
+pub mod focus;
+mod foreground;
+pub mod pattern;
+pub mod placement;
+mod runner;
+pub mod schedule;
+pub mod settings;
+mod watcher;
+
+pub use runner::start_automation;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::config::load_config;
+use crate::workspace::perform_switch;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AutomationSwitchEvent {
+    pub desktop_id: i32,
+    pub reason: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Переключает стол тем же путём, что и `switch_workspace`, и сообщает UI.
+/// Возвращает `true`, только если переключение действительно произошло.
+fn apply_switch(app: &AppHandle, desktop_id: i32, reason: &str) -> bool {
+    if load_config().active_desktop_id == desktop_id {
+        return false;
+    }
+
+    match perform_switch(desktop_id) {
+        Ok(()) => {
+            let _ = app.emit(
+                "automation-switch-desktop",
+                AutomationSwitchEvent {
+                    desktop_id,
+                    reason: reason.to_string(),
+                },
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("Автоматическое переключение не удалось: {}", e);
+            false
+        }
+    }
+}
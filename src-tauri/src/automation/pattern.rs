@@ -0,0 +1,101 @@
+//! Шаблоны окон: имя процесса и заголовок с подстановками `*` и `?`
+
+use serde::{Deserialize, Serialize};
+
+use crate::virtual_desktop::api::WindowInfo;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WindowPattern {
+    #[serde(default)]
+    pub process: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl WindowPattern {
+    /// Пустой шаблон не совпадает ни с чем, иначе должны совпасть все заданные поля
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        if self.process.is_none() && self.title.is_none() {
+            return false;
+        }
+
+        let process_ok = self
+            .process
+            .as_deref()
+            .is_none_or(|p| wildcard_match(p, &window.process_name));
+        let title_ok = self
+            .title
+            .as_deref()
+            .is_none_or(|p| wildcard_match(p, &window.title));
+
+        process_ok && title_ok
+    }
+}
+
+/// Сравнение без учёта регистра; `*` — любая строка, `?` — один символ
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Пусть последняя `*` поглотит ещё один символ
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wildcard_match;
+
+    #[test]
+    fn star_matches_any_substring() {
+        assert!(wildcard_match("*code*", "Visual Studio Code"));
+        assert!(wildcard_match("a*c", "abbbc"));
+        assert!(wildcard_match("a*c", "ac"));
+        assert!(!wildcard_match("a*c", "abd"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_char() {
+        assert!(wildcard_match("note?ad.exe", "notepad.exe"));
+        assert!(!wildcard_match("note?ad.exe", "notead.exe"));
+        assert!(!wildcard_match("?", ""));
+    }
+
+    #[test]
+    fn ignores_case() {
+        assert!(wildcard_match("CHROME.EXE", "chrome.exe"));
+        assert!(wildcard_match("*Telegram*", "TELEGRAM desktop"));
+    }
+
+    #[test]
+    fn empty_pattern_matches_only_empty_text() {
+        assert!(wildcard_match("", ""));
+        assert!(!wildcard_match("", "explorer.exe"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn trailing_star_matches_rest() {
+        assert!(wildcard_match("Inbox*", "Inbox"));
+        assert!(wildcard_match("Inbox*", "Inbox - Outlook"));
+        assert!(!wildcard_match("Inbox*", "My Inbox"));
+    }
+}
@@ -0,0 +1,166 @@
+//! Правила размещения окон: перенос на закреплённый стол и закрепление
+//! приложений на всех столах
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::pattern::WindowPattern;
+use super::settings::current_settings;
+use crate::config::load_config;
+use crate::virtual_desktop::api::{enumerate_windows, move_window, pin_app_of_window, WindowInfo};
+use crate::workspace::virtual_desktop_index_for;
+
+const MAX_LOG_ENTRIES: usize = 200;
+
+static PLACEMENT_LOG: Mutex<VecDeque<PlacementLogEntry>> = Mutex::new(VecDeque::new());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Data structures
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlacementRule {
+    pub id: u32,
+    pub enabled: bool,
+    /// Чем больше, тем раньше проверяется правило
+    #[serde(default)]
+    pub priority: i32,
+    pub desktop_id: i32,
+    pub include: Vec<WindowPattern>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PlacementLogEntry {
+    pub timestamp: u64,
+    pub hwnd: isize,
+    pub title: String,
+    pub process_name: String,
+    pub rule_id: u32,
+    pub desktop_id: i32,
+    pub virtual_desktop_index: Option<usize>,
+    pub error: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tauri Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_placement_log() -> Result<Vec<PlacementLogEntry>, String> {
+    let log = PLACEMENT_LOG.lock().map_err(|e| e.to_string())?;
+    Ok(log.iter().cloned().collect())
+}
+
+#[tauri::command]
+pub async fn clear_placement_log() -> Result<(), String> {
+    PLACEMENT_LOG.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
+
+/// Применяет правила ко всем уже открытым окнам
+#[tauri::command]
+pub async fn apply_placement_rules() -> Result<Vec<PlacementLogEntry>, String> {
+    let windows = enumerate_windows()?;
+
+    for window in &windows {
+        apply_pin_rules(window);
+    }
+
+    Ok(windows.iter().filter_map(place_window).collect())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Первое подходящее правило по приоритету; исключения имеют абсолютный приоритет
+pub fn select_rule<'a>(
+    window: &WindowInfo,
+    rules: &'a [PlacementRule],
+    exclusions: &[WindowPattern],
+) -> Option<&'a PlacementRule> {
+    if exclusions.iter().any(|p| p.matches(window)) {
+        return None;
+    }
+
+    let mut candidates: Vec<&PlacementRule> = rules
+        .iter()
+        .filter(|r| r.enabled && r.include.iter().any(|p| p.matches(window)))
+        .collect();
+
+    // Стабильная сортировка: при равном приоритете сохраняется порядок правил
+    candidates.sort_by_key(|r| std::cmp::Reverse(r.priority));
+    candidates.into_iter().next()
+}
+
+/// Переносит окно по правилам и пишет запись в журнал.
+/// Возвращает `None`, если правило не подошло или окно уже на своём столе.
+pub fn place_window(window: &WindowInfo) -> Option<PlacementLogEntry> {
+    let settings = current_settings();
+    if settings.paused {
+        return None;
+    }
+
+    let rule = select_rule(window, &settings.placement_rules, &settings.placement_exclusions)?;
+    let target = virtual_desktop_index_for(&load_config(), rule.desktop_id);
+
+    if target.is_some() && target == window.desktop_index {
+        return None;
+    }
+
+    let error = match target {
+        Some(index) => move_window(window.hwnd, index).err(),
+        None => Some("Рабочий стол Kimi не найден".to_string()),
+    };
+
+    let entry = PlacementLogEntry {
+        timestamp: unix_now(),
+        hwnd: window.hwnd,
+        title: window.title.clone(),
+        process_name: window.process_name.clone(),
+        rule_id: rule.id,
+        desktop_id: rule.desktop_id,
+        virtual_desktop_index: target,
+        error,
+    };
+
+    record(entry.clone());
+    Some(entry)
+}
+
+/// Закрепляет приложение на всех столах, если оно есть в списке `pinned_apps`
+pub fn apply_pin_rules(window: &WindowInfo) -> bool {
+    let settings = current_settings();
+
+    if settings.paused || window.is_app_pinned {
+        return false;
+    }
+
+    if !settings.pinned_apps.iter().any(|p| p.matches(window)) {
+        return false;
+    }
+
+    pin_app_of_window(window.hwnd).is_ok()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn record(entry: PlacementLogEntry) {
+    if let Ok(mut log) = PLACEMENT_LOG.lock() {
+        if log.len() >= MAX_LOG_ENTRIES {
+            log.pop_front();
+        }
+        log.push_back(entry);
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,83 @@
+//! Фоновый поток, вычисляющий правила автоматизации
+
+use tauri::AppHandle;
+
+#[cfg(windows)]
+const TICK_INTERVAL_SECS: u64 = 15;
+
+#[cfg(windows)]
+pub fn start_automation(app_handle: AppHandle) {
+    use super::schedule::Scheduler;
+    use super::settings::current_settings;
+    use std::thread;
+    use std::time::Duration;
+
+    super::foreground::start_focus_watcher(app_handle.clone());
+    super::watcher::start_window_watcher();
+
+    thread::spawn(move || {
+        let clock = platform::SystemClock;
+        let mut scheduler = Scheduler::default();
+
+        loop {
+            let settings = current_settings();
+
+            if !settings.paused {
+                if let Some(desktop_id) = scheduler.tick(&clock, &settings.schedule) {
+                    super::apply_switch(&app_handle, desktop_id, "schedule");
+                }
+            }
+
+            thread::sleep(Duration::from_secs(TICK_INTERVAL_SECS));
+        }
+    });
+}
+
+#[cfg(not(windows))]
+pub fn start_automation(app_handle: AppHandle) {
+    super::foreground::start_focus_watcher(app_handle);
+    super::watcher::start_window_watcher();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use super::super::schedule::{Clock, LocalTime};
+    use std::time::Duration;
+    use windows::Win32::System::SystemInformation::{GetLocalTime, GetTickCount};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub struct SystemClock;
+
+    impl Clock for SystemClock {
+        fn now(&self) -> LocalTime {
+            let st = unsafe { GetLocalTime() };
+
+            LocalTime {
+                year: st.wYear,
+                month: st.wMonth as u8,
+                day: st.wDay as u8,
+                weekday: st.wDayOfWeek as u8,
+                hour: st.wHour as u8,
+                minute: st.wMinute as u8,
+            }
+        }
+
+        fn idle_time(&self) -> Duration {
+            let mut info = LASTINPUTINFO {
+                cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+                dwTime: 0,
+            };
+
+            unsafe {
+                if !GetLastInputInfo(&mut info).as_bool() {
+                    return Duration::ZERO;
+                }
+                Duration::from_millis(GetTickCount().wrapping_sub(info.dwTime) as u64)
+            }
+        }
+    }
+}
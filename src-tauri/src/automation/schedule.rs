@@ -0,0 +1,244 @@
+//! Правила расписания и их вычисление по подставляемым часам `Clock`
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Data structures
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleRule {
+    pub id: u32,
+    pub enabled: bool,
+    pub desktop_id: i32,
+    pub trigger: ScheduleTrigger,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    /// Дни недели как в SYSTEMTIME: 0 = воскресенье … 6 = суббота
+    At { days: Vec<u8>, hour: u8, minute: u8 },
+    Idle { minutes: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub weekday: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+pub trait Clock {
+    fn now(&self) -> LocalTime;
+    fn idle_time(&self) -> Duration;
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Scheduler
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    last_fired: HashMap<u32, LocalTime>,
+    idle_fired: HashSet<u32>,
+}
+
+impl Scheduler {
+    /// Возвращает стол, на который нужно переключиться, если сработало правило.
+    /// Правила проверяются по порядку — первое сработавшее выигрывает.
+    pub fn tick(&mut self, clock: &dyn Clock, rules: &[ScheduleRule]) -> Option<i32> {
+        let now = clock.now();
+        let idle = clock.idle_time();
+        let mut target = None;
+
+        for rule in rules.iter().filter(|r| r.enabled) {
+            let fired = match &rule.trigger {
+                ScheduleTrigger::At { days, hour, minute } => {
+                    let due = days.contains(&now.weekday) && *hour == now.hour && *minute == now.minute;
+                    // Срабатываем один раз за минуту, сколько бы тиков в неё ни попало
+                    due && self.last_fired.insert(rule.id, now) != Some(now)
+                }
+                ScheduleTrigger::Idle { minutes } => {
+                    let threshold = Duration::from_secs(*minutes as u64 * 60);
+
+                    if idle >= threshold {
+                        self.idle_fired.insert(rule.id)
+                    } else {
+                        // Пользователь вернулся — правило снова взводится
+                        self.idle_fired.remove(&rule.id);
+                        false
+                    }
+                }
+            };
+
+            if fired && target.is_none() {
+                target = Some(rule.desktop_id);
+            }
+        }
+
+        target
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Подставные часы: время и простой задаются тестом
+    struct FakeClock {
+        now: Cell<LocalTime>,
+        idle: Cell<Duration>,
+    }
+
+    impl FakeClock {
+        fn at(day: u8, weekday: u8, hour: u8, minute: u8) -> Self {
+            Self {
+                now: Cell::new(time(day, weekday, hour, minute)),
+                idle: Cell::new(Duration::ZERO),
+            }
+        }
+
+        fn set(&self, day: u8, weekday: u8, hour: u8, minute: u8) {
+            self.now.set(time(day, weekday, hour, minute));
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> LocalTime {
+            self.now.get()
+        }
+
+        fn idle_time(&self) -> Duration {
+            self.idle.get()
+        }
+    }
+
+    fn time(day: u8, weekday: u8, hour: u8, minute: u8) -> LocalTime {
+        LocalTime {
+            year: 2026,
+            month: 10,
+            day,
+            weekday,
+            hour,
+            minute,
+        }
+    }
+
+    fn at_rule(id: u32, desktop_id: i32, days: &[u8], hour: u8, minute: u8) -> ScheduleRule {
+        ScheduleRule {
+            id,
+            enabled: true,
+            desktop_id,
+            trigger: ScheduleTrigger::At {
+                days: days.to_vec(),
+                hour,
+                minute,
+            },
+        }
+    }
+
+    fn idle_rule(id: u32, desktop_id: i32, minutes: u32) -> ScheduleRule {
+        ScheduleRule {
+            id,
+            enabled: true,
+            desktop_id,
+            trigger: ScheduleTrigger::Idle { minutes },
+        }
+    }
+
+    #[test]
+    fn fires_when_due_and_not_before() {
+        // 19.10.2026 — понедельник
+        let clock = FakeClock::at(19, 1, 8, 59);
+        let rules = [at_rule(1, 2, &[1, 2, 3, 4, 5], 9, 0)];
+        let mut scheduler = Scheduler::default();
+
+        assert_eq!(scheduler.tick(&clock, &rules), None);
+
+        clock.set(19, 1, 9, 0);
+        assert_eq!(scheduler.tick(&clock, &rules), Some(2));
+    }
+
+    #[test]
+    fn fires_once_per_minute() {
+        let clock = FakeClock::at(19, 1, 9, 0);
+        let rules = [at_rule(1, 2, &[1], 9, 0)];
+        let mut scheduler = Scheduler::default();
+
+        assert_eq!(scheduler.tick(&clock, &rules), Some(2));
+        assert_eq!(scheduler.tick(&clock, &rules), None);
+    }
+
+    #[test]
+    fn skips_days_not_in_rule() {
+        // 24.10.2026 — суббота
+        let clock = FakeClock::at(24, 6, 9, 0);
+        let rules = [at_rule(1, 2, &[1, 2, 3, 4, 5], 9, 0)];
+
+        assert_eq!(Scheduler::default().tick(&clock, &rules), None);
+    }
+
+    #[test]
+    fn missed_window_does_not_fire_late() {
+        // Тик пришёлся на 9:02 — переключение в 9:00 пропущено и не догоняется
+        let clock = FakeClock::at(19, 1, 8, 59);
+        let rules = [at_rule(1, 2, &[1], 9, 0)];
+        let mut scheduler = Scheduler::default();
+
+        assert_eq!(scheduler.tick(&clock, &rules), None);
+
+        clock.set(19, 1, 9, 2);
+        assert_eq!(scheduler.tick(&clock, &rules), None);
+    }
+
+    #[test]
+    fn fires_again_after_day_rollover() {
+        let clock = FakeClock::at(19, 1, 23, 59);
+        let rules = [at_rule(1, 2, &[1, 2], 23, 59)];
+        let mut scheduler = Scheduler::default();
+
+        assert_eq!(scheduler.tick(&clock, &rules), Some(2));
+
+        clock.set(20, 2, 0, 0);
+        assert_eq!(scheduler.tick(&clock, &rules), None);
+
+        clock.set(20, 2, 23, 59);
+        assert_eq!(scheduler.tick(&clock, &rules), Some(2));
+    }
+
+    #[test]
+    fn idle_rule_rearms_after_activity() {
+        let clock = FakeClock::at(19, 1, 12, 0);
+        let rules = [idle_rule(1, 3, 10)];
+        let mut scheduler = Scheduler::default();
+
+        clock.idle.set(Duration::from_secs(11 * 60));
+        assert_eq!(scheduler.tick(&clock, &rules), Some(3));
+        assert_eq!(scheduler.tick(&clock, &rules), None);
+
+        clock.idle.set(Duration::ZERO);
+        assert_eq!(scheduler.tick(&clock, &rules), None);
+
+        clock.idle.set(Duration::from_secs(10 * 60));
+        assert_eq!(scheduler.tick(&clock, &rules), Some(3));
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let clock = FakeClock::at(19, 1, 9, 0);
+        let rules = [at_rule(1, 2, &[1], 9, 0), at_rule(2, 4, &[1], 9, 0)];
+
+        assert_eq!(Scheduler::default().tick(&clock, &rules), Some(2));
+    }
+}
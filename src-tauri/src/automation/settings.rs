@@ -0,0 +1,130 @@
+//! Настройки автоматизации и команды для их редактирования
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use super::focus::FocusRule;
+use super::pattern::WindowPattern;
+use super::placement::PlacementRule;
+use super::schedule::ScheduleRule;
+
+const SETTINGS_FILE: &str = "C:\\Kimi\\automation.json";
+
+static SETTINGS: OnceLock<Mutex<AutomationSettings>> = OnceLock::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Data structures
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AutomationSettings {
+    pub paused: bool,
+    pub schedule: Vec<ScheduleRule>,
+    pub focus_rules: Vec<FocusRule>,
+    pub focus_cooldown_secs: u64,
+    pub placement_rules: Vec<PlacementRule>,
+    pub placement_exclusions: Vec<WindowPattern>,
+    /// Приложения, которые всегда закрепляются на всех столах
+    pub pinned_apps: Vec<WindowPattern>,
+}
+
+impl Default for AutomationSettings {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            schedule: Vec::new(),
+            focus_rules: Vec::new(),
+            focus_cooldown_secs: 10,
+            placement_rules: Vec::new(),
+            placement_exclusions: Vec::new(),
+            pinned_apps: Vec::new(),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Settings persistence
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn current_settings() -> AutomationSettings {
+    SETTINGS
+        .get_or_init(|| Mutex::new(load_automation_settings()))
+        .lock()
+        .map(|g| g.clone())
+        .unwrap_or_default()
+}
+
+fn load_automation_settings() -> AutomationSettings {
+    let path = PathBuf::from(SETTINGS_FILE);
+
+    if path.exists() {
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    } else {
+        AutomationSettings::default()
+    }
+}
+
+fn store_settings(settings: AutomationSettings) -> Result<(), String> {
+    let path = PathBuf::from(SETTINGS_FILE);
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+
+    SETTINGS
+        .get_or_init(|| Mutex::new(AutomationSettings::default()))
+        .lock()
+        .map(|mut g| *g = settings)
+        .map_err(|e| e.to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tauri Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_automation_settings() -> Result<AutomationSettings, String> {
+    Ok(current_settings())
+}
+
+#[tauri::command]
+pub async fn set_automation_settings(mut settings: AutomationSettings) -> Result<AutomationSettings, String> {
+    assign_ids(&mut settings.schedule, |r| &mut r.id);
+    assign_ids(&mut settings.focus_rules, |r| &mut r.id);
+    assign_ids(&mut settings.placement_rules, |r| &mut r.id);
+    store_settings(settings.clone())?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn set_automation_paused(paused: bool) -> Result<(), String> {
+    let mut settings = current_settings();
+    settings.paused = paused;
+    store_settings(settings)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Новым правилам из UI (id = 0) выдаём следующий свободный id
+fn assign_ids<T>(rules: &mut [T], id_of: impl Fn(&mut T) -> &mut u32) {
+    let first_free = rules.iter_mut().map(|r| *id_of(r)).max().unwrap_or(0) + 1;
+    let unassigned = rules.iter_mut().filter_map(|r| {
+        let id = id_of(r);
+        (*id == 0).then_some(id)
+    });
+
+    for (next, id) in (first_free..).zip(unassigned) {
+        *id = next;
+    }
+}
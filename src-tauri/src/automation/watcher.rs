@@ -0,0 +1,135 @@
+//! Отслеживание появления новых окон (WinEvent hook) для правил размещения
+
+#[cfg(windows)]
+const NEW_WINDOW_SETTLE_MS: u64 = 500;
+
+#[cfg(windows)]
+pub fn start_window_watcher() {
+    hook::start();
+}
+
+#[cfg(not(windows))]
+pub fn start_window_watcher() {}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Windows event hook
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod hook {
+    use super::NEW_WINDOW_SETTLE_MS;
+    use crate::automation::placement::{apply_pin_rules, place_window};
+    use crate::automation::settings::current_settings;
+    use crate::virtual_desktop::api::{get_window_info, is_task_view_window};
+    use std::collections::HashSet;
+    use std::sync::mpsc::{channel, Receiver, Sender};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Duration;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetAncestor, GetMessageW, IsWindow, EVENT_OBJECT_SHOW, GA_ROOT, MSG, OBJID_WINDOW,
+        WINEVENT_OUTOFCONTEXT,
+    };
+
+    const CHILDID_SELF: i32 = 0;
+    const MAX_TRACKED_WINDOWS: usize = 512;
+
+    static SHOWN_TX: OnceLock<Mutex<Sender<isize>>> = OnceLock::new();
+
+    pub fn start() {
+        let (tx, rx) = channel();
+        if SHOWN_TX.set(Mutex::new(tx)).is_err() {
+            return;
+        }
+
+        std::thread::spawn(|| unsafe {
+            let hook = SetWinEventHook(
+                EVENT_OBJECT_SHOW,
+                EVENT_OBJECT_SHOW,
+                None,
+                Some(win_event_proc),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            );
+
+            if !hook.is_invalid() {
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, None, 0, 0).0 > 0 {}
+                let _ = UnhookWinEvent(hook);
+            }
+        });
+
+        std::thread::spawn(move || placement_loop(rx));
+    }
+
+    unsafe extern "system" fn win_event_proc(
+        _hook: HWINEVENTHOOK,
+        _event: u32,
+        hwnd: HWND,
+        id_object: i32,
+        id_child: i32,
+        _event_thread: u32,
+        _event_time: u32,
+    ) {
+        // Интересуют только сами окна, а не их дочерние объекты
+        if id_object != OBJID_WINDOW.0 || id_child != CHILDID_SELF {
+            return;
+        }
+
+        if let Some(tx) = SHOWN_TX.get() {
+            if let Ok(tx) = tx.lock() {
+                let _ = tx.send(hwnd.0 as isize);
+            }
+        }
+    }
+
+    fn placement_loop(rx: Receiver<isize>) {
+        // Окна, которые уже обработаны: повторный показ не должен уносить окно,
+        // которое пользователь сам переложил на другой стол
+        let mut handled: HashSet<isize> = HashSet::new();
+
+        while let Ok(first) = rx.recv() {
+            // Новому окну нужно время, чтобы получить заголовок
+            let mut batch = vec![first];
+            while let Ok(next) = rx.recv_timeout(Duration::from_millis(NEW_WINDOW_SETTLE_MS)) {
+                batch.push(next);
+            }
+
+            let settings = current_settings();
+            if settings.placement_rules.is_empty() && settings.pinned_apps.is_empty() {
+                continue;
+            }
+
+            for hwnd in batch {
+                if handled.contains(&hwnd) || !is_top_level(hwnd) {
+                    continue;
+                }
+
+                let Some(window) = get_window_info(hwnd) else {
+                    continue;
+                };
+
+                handled.insert(hwnd);
+
+                // Диалоги и вспомогательные окна следуют за своим владельцем
+                if window.process_id != std::process::id() && is_task_view_window(&window) {
+                    // Закреплённое приложение и так видно на всех столах
+                    if !apply_pin_rules(&window) {
+                        place_window(&window);
+                    }
+                }
+            }
+
+            if handled.len() > MAX_TRACKED_WINDOWS {
+                handled.retain(|&h| unsafe { IsWindow(HWND(h as *mut _)).as_bool() });
+            }
+        }
+    }
+
+    fn is_top_level(hwnd: isize) -> bool {
+        let hwnd = HWND(hwnd as *mut _);
+        unsafe { GetAncestor(hwnd, GA_ROOT) == hwnd }
+    }
+}
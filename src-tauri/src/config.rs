@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 pub const KIMI_DIR: &str = "C:\\Kimi";
@@ -38,6 +38,54 @@ pub struct KimiConfig {
     pub active_desktop_id: i32,
     pub original_desktop_path: String,
     pub virtual_desktop_mapping: HashMap<i32, String>,
+    #[serde(default)]
+    pub name_sync: NameSyncSettings,
+    /// Применять стол Kimi при переключении виртуального стола вне Kimi
+    #[serde(default = "default_true")]
+    pub follow_virtual_desktops: bool,
+    #[serde(default)]
+    pub icon_autosave: IconAutosaveSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NameSyncSettings {
+    pub enabled: bool,
+    pub conflict_policy: NameConflictPolicy,
+}
+
+impl Default for NameSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            conflict_policy: NameConflictPolicy::PreferKimi,
+        }
+    }
+}
+
+/// Фоновое сохранение позиций иконок активного стола
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IconAutosaveSettings {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for IconAutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+        }
+    }
+}
+
+/// Чьё имя побеждает, если при синхронизации имена разошлись
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NameConflictPolicy {
+    PreferKimi,
+    PreferWindows,
+    /// Ничего не менять, только сообщить о конфликте
+    Skip,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -71,6 +119,9 @@ pub fn load_config() -> KimiConfig {
         active_desktop_id: 0,
         original_desktop_path: get_default_desktop_path(),
         virtual_desktop_mapping: HashMap::new(),
+        name_sync: NameSyncSettings::default(),
+        follow_virtual_desktops: true,
+        icon_autosave: IconAutosaveSettings::default(),
     }
 }
 
@@ -85,6 +136,15 @@ pub fn get_desktop_folder_path(id: i32) -> PathBuf {
     PathBuf::from(KIMI_DIR).join(format!("Desktop{}", id))
 }
 
+/// Обратное к `get_desktop_folder_path`: id стола по его папке
+pub fn desktop_id_for_folder(path: &Path) -> Option<i32> {
+    if path.parent()? != Path::new(KIMI_DIR) {
+        return None;
+    }
+
+    path.file_name()?.to_str()?.strip_prefix("Desktop")?.parse().ok()
+}
+
 pub fn count_files_in_dir(path: &PathBuf) -> i32 {
     fs::read_dir(path)
         .map(|entries| entries.filter_map(|e| e.ok()).count() as i32)
@@ -114,6 +174,10 @@ fn get_default_desktop_path() -> String {
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
 fn fallback_desktop_path() -> String {
     let profile = std::env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\Default".to_string());
     format!("{}\\Desktop", profile)
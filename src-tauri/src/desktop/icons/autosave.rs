@@ -0,0 +1,55 @@
+//! Фоновое сохранение позиций иконок активного стола
+
+use std::thread;
+use std::time::Duration;
+
+use super::storage::save_layout;
+use super::{get_current_icon_positions, load_icon_positions};
+use crate::config::{get_desktop_folder_path, load_config, CONFIG_LOCK};
+
+/// Чаще опрашивать Explorer незачем
+const MIN_INTERVAL_SECS: u64 = 10;
+
+pub fn start_icon_autosave() {
+    thread::spawn(|| loop {
+        let settings = load_config().icon_autosave;
+        thread::sleep(Duration::from_secs(settings.interval_secs.max(MIN_INTERVAL_SECS)));
+
+        if !load_config().icon_autosave.enabled {
+            continue;
+        }
+
+        if let Err(e) = save_if_changed() {
+            eprintln!("Автосохранение иконок: {}", e);
+        }
+    });
+}
+
+/// `true`, если раскладка изменилась и была записана
+fn save_if_changed() -> Result<bool, String> {
+    // Переключение держит CONFIG_LOCK: посреди него на экране иконки чужого стола
+    let Ok(_lock) = CONFIG_LOCK.try_lock() else {
+        return Ok(false);
+    };
+
+    let config = load_config();
+    if config.active_desktop_id <= 0 {
+        return Ok(false);
+    }
+
+    let live = get_current_icon_positions()?;
+
+    // Ни одной иконки — скорее всего, Explorer как раз перезапускается
+    if live.icons.is_empty() {
+        return Ok(false);
+    }
+
+    let path = get_desktop_folder_path(config.active_desktop_id);
+    if load_icon_positions(&path).same_positions(&live) {
+        return Ok(false);
+    }
+
+    // Обычные перестановки не должны вытеснять из истории снимки переключений
+    save_layout(&path, &live, false)?;
+    Ok(true)
+}
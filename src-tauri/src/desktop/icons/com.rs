@@ -1,7 +1,16 @@
 //! Windows COM API для работы с иконками рабочего стола (IFolderView2)
 
+use super::display::current_display;
+use super::grid::{current_grid, snap_positions};
+use super::identity::follow_renames;
 use super::layout::{DesktopIconsLayout, IconPosition};
-use std::path::PathBuf;
+use super::policy::{load_policy, place_new_icons};
+use super::report::{record_report, IconFailure, RestoreReport};
+use super::storage::save_layout;
+use crate::config::desktop_id_for_folder;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use windows::core::{Interface, HSTRING, VARIANT};
 use windows::Win32::Foundation::POINT;
@@ -17,7 +26,19 @@ use windows::Win32::UI::Shell::{
 
 const FWF_AUTOARRANGE: u32 = 0x00000001;
 const FWF_SNAPTOGRID: u32 = 0x00000002;
-const GRID_SIZE: i32 = 75;
+
+const WAIT_ATTEMPTS: u32 = 15;
+const WAIT_INTERVAL: Duration = Duration::from_millis(200);
+/// Повторные попытки для иконок, которые Explorer показал не сразу
+const RETRY_ATTEMPTS: u32 = 10;
+const RETRY_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Почему иконку не удалось поставить
+enum PositionError {
+    /// Explorer не знает элемента с таким именем
+    NotFound,
+    Failed(String),
+}
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Public API
@@ -26,13 +47,16 @@ const GRID_SIZE: i32 = 75;
 pub fn get_current_icon_positions() -> Result<DesktopIconsLayout, String> {
     unsafe {
         let folder_view = get_desktop_folder_view()?;
-        let mut layout = DesktopIconsLayout::default();
+        let mut layout = DesktopIconsLayout {
+            display: current_display(),
+            ..Default::default()
+        };
 
         let count = folder_view
             .ItemCount(SVGIO_BACKGROUND)
             .map_err(|e| format!("ItemCount: {}", e))?;
 
-        for i in 0..count as i32 {
+        for i in 0..count {
             if let Ok(pidl) = folder_view.Item(i) {
                 if !pidl.is_null() {
                     if let (Ok(point), Ok(name)) = (folder_view.GetItemPosition(pidl), get_item_name(pidl)) {
@@ -49,21 +73,51 @@ pub fn get_current_icon_positions() -> Result<DesktopIconsLayout, String> {
     }
 }
 
-pub fn restore_icon_positions(layout: &DesktopIconsLayout, _desktop_path: &PathBuf) -> Result<(), String> {
+/// Расставляет иконки по раскладке. Отчёт записывается и отправляется
+/// в интерфейс, даже если восстановление прервалось ошибкой
+pub fn restore_icon_positions(layout: &DesktopIconsLayout, desktop_path: &Path) -> Result<RestoreReport, String> {
+    let mut report = RestoreReport::new(desktop_id_for_folder(desktop_path));
 
     if layout.icons.is_empty() {
+        record_report(&report);
+        return Ok(report);
+    }
+
+    let result = restore_layout(layout, desktop_path, &mut report);
+    if let Err(error) = &result {
+        report.error = Some(error.clone());
+    }
+
+    record_report(&report);
+    result.map(|()| report)
+}
+
+/// Шаг сетки значков в окне рабочего стола
+pub fn icon_spacing() -> Result<(i32, i32), String> {
+    unsafe {
+        let folder_view = get_desktop_folder_view()?;
+        let mut spacing = POINT::default();
+
+        folder_view
+            .GetSpacing(&mut spacing)
+            .map_err(|e| format!("GetSpacing: {}", e))?;
+
+        Ok((spacing.x, spacing.y))
+    }
+}
+
+/// Ставит отдельные иконки, не трогая остальные
+pub fn position_icons(icons: &[(String, IconPosition)]) -> Result<(), String> {
+    if icons.is_empty() {
         return Ok(());
     }
 
     unsafe {
         let folder_view = get_desktop_folder_view()?;
         let desktop_folder = get_shell_folder(&folder_view)?;
-        
-        disable_auto_arrange(&folder_view)?;
-        wait_for_icons(&folder_view, layout.icons.len())?;
-        
-        for (filename, pos) in &layout.icons {
-            position_icon(&folder_view, &desktop_folder, filename, pos);
+
+        for (filename, pos) in icons {
+            let _ = position_icon(&folder_view, &desktop_folder, filename, pos);
         }
 
         Ok(())
@@ -81,6 +135,89 @@ pub fn disable_auto_arrange_public() -> Result<(), String> {
 // Internal helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
+fn restore_layout(
+    layout: &DesktopIconsLayout,
+    desktop_path: &Path,
+    report: &mut RestoreReport,
+) -> Result<(), String> {
+    // Переименованные файлы находим по идентичности и сразу запоминаем новые имена
+    let renamed = follow_renames(layout, desktop_path);
+    if let Some(renamed) = &renamed {
+        let _ = save_layout(desktop_path, renamed, false);
+    }
+    let layout = renamed.as_ref().unwrap_or(layout);
+
+    // Раскладка могла быть снята при другом разрешении или масштабе
+    let display = current_display();
+    let fitted;
+    let layout = match &display {
+        Some(display) => {
+            fitted = layout.fitted_to(display);
+            &fitted
+        }
+        None => layout,
+    };
+
+    let policy = report.desktop_id.map(load_policy).unwrap_or_default();
+
+    unsafe {
+        let folder_view = get_desktop_folder_view()?;
+        let desktop_folder = get_shell_folder(&folder_view)?;
+        
+        disable_auto_arrange(&folder_view)?;
+
+        let (waited, timed_out) = wait_for_icons(&folder_view, layout.icons.len());
+        report.waited_ms = waited.as_millis() as u64;
+        report.wait_timed_out = timed_out;
+
+        let grid = display.as_ref().map(current_grid);
+
+        let positions: Vec<(String, IconPosition)> = match (&display, &grid) {
+            (Some(display), Some(grid)) if policy.snap_to_grid => {
+                snap_positions(grid, &display.client_areas(), &layout.icons)
+            }
+            _ => layout.icons.iter().map(|(n, p)| (n.clone(), p.clone())).collect(),
+        };
+
+        let mut late = Vec::new();
+
+        for (filename, pos) in &positions {
+            match position_icon(&folder_view, &desktop_folder, filename, pos) {
+                Ok(()) => report.placed.push(filename.clone()),
+                // Explorer мог ещё не показать элемент: файлы, иконки общего
+                // рабочего стола и системные `::{GUID}` подгружаются не сразу
+                Err(PositionError::NotFound) => late.push((filename.clone(), pos.clone())),
+                Err(PositionError::Failed(error)) => report.failed.push(IconFailure {
+                    name: filename.clone(),
+                    error,
+                }),
+            }
+        }
+
+        retry_late_icons(&folder_view, &desktop_folder, late, report);
+
+        // Иконки, которых нет в раскладке, расставляем по политике стола
+        if let (false, Some(display), Some(grid)) = (policy.has_no_rules(), &display, &grid) {
+            let live = get_current_icon_positions()?;
+            let new_names: Vec<String> = live
+                .icons
+                .keys()
+                .filter(|name| !layout.icons.contains_key(*name))
+                .cloned()
+                .collect();
+            let mut occupied: Vec<(i32, i32)> = positions.iter().map(|(_, p)| (p.x, p.y)).collect();
+
+            for (filename, pos) in place_new_icons(&policy, &new_names, &mut occupied, display, grid) {
+                if position_icon(&folder_view, &desktop_folder, &filename, &pos).is_ok() {
+                    report.placed_by_policy.push(filename);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 unsafe fn get_desktop_folder_view() -> Result<IFolderView2, String> {
     let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
 
@@ -158,20 +295,53 @@ unsafe fn disable_auto_arrange(folder_view: &IFolderView2) -> Result<(), String>
         .map_err(|e| format!("SetCurrentFolderFlags: {}", e))
 }
 
-unsafe fn wait_for_icons(folder_view: &IFolderView2, expected: usize) -> Result<(), String> {
-    use std::{thread, time::Duration};
-    
+/// Ждёт, пока Explorer загрузит хотя бы половину иконок раскладки.
+/// Возвращает время ожидания и признак того, что не дождались
+unsafe fn wait_for_icons(folder_view: &IFolderView2, expected: usize) -> (Duration, bool) {
+    let started = Instant::now();
     let expected = expected as i32;
     
-    for _ in 0..15 {
+    for _ in 0..WAIT_ATTEMPTS {
         let current = folder_view.ItemCount(SVGIO_BACKGROUND).unwrap_or(0);
         if current >= 1 && current >= expected / 2 {
-            return Ok(());
+            return (started.elapsed(), false);
         }
-        thread::sleep(Duration::from_millis(200));
+        thread::sleep(WAIT_INTERVAL);
     }
     
-    Ok(()) // Timeout — пробуем расставить что есть
+    (started.elapsed(), true) // Timeout — пробуем расставить что есть
+}
+
+/// Повторяет попытки для иконок, которые появились не сразу
+unsafe fn retry_late_icons(
+    folder_view: &IFolderView2,
+    desktop_folder: &IShellFolder,
+    mut late: Vec<(String, IconPosition)>,
+    report: &mut RestoreReport,
+) {
+    let started = Instant::now();
+
+    for _ in 0..RETRY_ATTEMPTS {
+        if late.is_empty() {
+            break;
+        }
+        thread::sleep(RETRY_INTERVAL);
+
+        let mut still_missing = Vec::new();
+
+        for (filename, pos) in late {
+            match position_icon(folder_view, desktop_folder, &filename, &pos) {
+                Ok(()) => report.placed_on_retry.push(filename),
+                Err(PositionError::NotFound) => still_missing.push((filename, pos)),
+                Err(PositionError::Failed(error)) => report.failed.push(IconFailure { name: filename, error }),
+            }
+        }
+
+        late = still_missing;
+    }
+
+    report.waited_ms += started.elapsed().as_millis() as u64;
+    report.not_found.extend(late.into_iter().map(|(filename, _)| filename));
 }
 
 unsafe fn position_icon(
@@ -179,7 +349,7 @@ unsafe fn position_icon(
     desktop_folder: &IShellFolder,
     filename: &str,
     pos: &IconPosition,
-) {
+) -> Result<(), PositionError> {
     let name_hstring = HSTRING::from(filename);
     let mut pidl: *mut ITEMIDLIST = std::ptr::null_mut();
 
@@ -192,22 +362,21 @@ unsafe fn position_icon(
         std::ptr::null_mut(),
     );
 
-    if hr.is_ok() && !pidl.is_null() {
-        let (x, y) = snap_to_grid(pos.x, pos.y);
-        let point = POINT { x, y };
-        let pidl_array = [pidl as *const ITEMIDLIST];
+    if hr.is_err() || pidl.is_null() {
+        return Err(PositionError::NotFound);
+    }
+
+    let point = POINT { x: pos.x, y: pos.y };
+    let pidl_array = [pidl as *const ITEMIDLIST];
 
-        let _ = folder_view.SelectAndPositionItems(
-            1,
-            pidl_array.as_ptr(),
-            Some(&point),
-            SVSI_POSITIONITEM.0 as u32,
-        );
+    let result = folder_view.SelectAndPositionItems(
+        1,
+        pidl_array.as_ptr(),
+        Some(&point),
+        SVSI_POSITIONITEM.0 as u32,
+    );
 
-        CoTaskMemFree(Some(pidl as *const _));
-    }
-}
+    CoTaskMemFree(Some(pidl as *const _));
 
-fn snap_to_grid(x: i32, y: i32) -> (i32, i32) {
-    ((x / GRID_SIZE) * GRID_SIZE, (y / GRID_SIZE) * GRID_SIZE)
+    result.map_err(|e| PositionError::Failed(format!("SelectAndPositionItems: {}", e)))
 }
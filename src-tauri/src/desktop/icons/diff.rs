@@ -0,0 +1,144 @@
+//! Сравнение раскладок иконок
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::layout::{DesktopIconsLayout, IconPosition};
+
+/// Служебные файлы в папке стола, которые не являются иконками
+const SERVICE_FILES: &[&str] = &["coords.kimi", "coords.history.kimi", "desktop.ini"];
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LayoutSource {
+    Saved,
+    Live,
+    Version { id: u64 },
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct LayoutDiff {
+    pub moved: Vec<MovedIcon>,
+    /// Есть в исходной раскладке, нет в конечной
+    pub only_in_from: Vec<String>,
+    /// Есть в конечной раскладке, нет в исходной
+    pub only_in_to: Vec<String>,
+    /// Файлы в папке стола, которых нет в исходной раскладке
+    pub not_in_layout: Vec<String>,
+    /// Записи исходной раскладки, чьих файлов больше нет
+    pub missing_files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MovedIcon {
+    pub name: String,
+    pub from: IconPosition,
+    pub to: IconPosition,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Сравнение двух раскладок без обращения к диску
+pub fn diff_layouts(from: &DesktopIconsLayout, to: &DesktopIconsLayout) -> LayoutDiff {
+    let mut diff = LayoutDiff::default();
+
+    for (name, from_pos) in &from.icons {
+        match to.icons.get(name) {
+            Some(to_pos) if to_pos != from_pos => diff.moved.push(MovedIcon {
+                name: name.clone(),
+                from: from_pos.clone(),
+                to: to_pos.clone(),
+                dx: to_pos.x - from_pos.x,
+                dy: to_pos.y - from_pos.y,
+            }),
+            Some(_) => {}
+            None => diff.only_in_from.push(name.clone()),
+        }
+    }
+
+    diff.only_in_to = to
+        .icons
+        .keys()
+        .filter(|name| !from.icons.contains_key(*name))
+        .cloned()
+        .collect();
+
+    diff.moved.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.only_in_from.sort();
+    diff.only_in_to.sort();
+    diff
+}
+
+/// Дополняет сравнение сверкой исходной раскладки с файлами папки стола
+pub fn check_files(diff: &mut LayoutDiff, from: &DesktopIconsLayout, desktop_path: &Path) {
+    // Ярлыки общего стола Explorer показывает на каждом столе
+    let mut files = desktop_files(desktop_path);
+    if let Some(public) = platform::public_desktop_path() {
+        files.extend(desktop_files(&public));
+    }
+
+    diff.not_in_layout = files
+        .iter()
+        .filter(|name| !from.icons.contains_key(*name))
+        .cloned()
+        .collect();
+
+    // Системные элементы вроде Корзины (`::{GUID}`) файлами не являются
+    diff.missing_files = from
+        .icons
+        .keys()
+        .filter(|name| !name.starts_with("::") && !files.contains(*name))
+        .cloned()
+        .collect();
+
+    diff.not_in_layout.sort();
+    diff.missing_files.sort();
+}
+
+/// Имена файлов и папок стола без служебных файлов Kimi
+pub fn desktop_files(desktop_path: &Path) -> HashSet<String> {
+    fs::read_dir(desktop_path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| !SERVICE_FILES.iter().any(|s| name.eq_ignore_ascii_case(s)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use std::path::PathBuf;
+    use windows::Win32::System::Com::CoTaskMemFree;
+    use windows::Win32::UI::Shell::{FOLDERID_PublicDesktop, SHGetKnownFolderPath, KF_FLAG_DEFAULT};
+
+    pub fn public_desktop_path() -> Option<PathBuf> {
+        unsafe {
+            let path = SHGetKnownFolderPath(&FOLDERID_PublicDesktop, KF_FLAG_DEFAULT, None).ok()?;
+            let result = path.to_string().ok().map(PathBuf::from);
+            CoTaskMemFree(Some(path.0 as *const _));
+            result
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::path::PathBuf;
+
+    pub fn public_desktop_path() -> Option<PathBuf> {
+        None
+    }
+}
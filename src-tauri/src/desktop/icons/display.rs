@@ -0,0 +1,269 @@
+//! Геометрия дисплея, при которой сохранена раскладка, и подгонка позиций под текущую
+
+use serde::{Deserialize, Serialize};
+
+/// Ячейка иконки при 100% масштабе — запас, чтобы иконка не уехала за край
+const BASE_CELL: i32 = 75;
+const BASE_DPI: i32 = 96;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LayoutRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DisplayGeometry {
+    /// Виртуальный экран (все мониторы) в экранных координатах
+    pub bounds: LayoutRect,
+    /// Рабочая область основного монитора — без панели задач
+    pub work_area: LayoutRect,
+    pub dpi: u32,
+    /// Прямоугольники подключённых мониторов, по ним строится отпечаток
+    #[serde(default)]
+    pub monitors: Vec<LayoutRect>,
+    /// Рабочие области мониторов в том же порядке, что и `monitors`
+    #[serde(default)]
+    pub work_areas: Vec<LayoutRect>,
+}
+
+impl DisplayGeometry {
+    /// Рабочая область в координатах окна рабочего стола (как у позиций иконок)
+    pub fn client_work_area(&self) -> LayoutRect {
+        LayoutRect {
+            left: self.work_area.left - self.bounds.left,
+            top: self.work_area.top - self.bounds.top,
+            right: self.work_area.right - self.bounds.left,
+            bottom: self.work_area.bottom - self.bounds.top,
+        }
+    }
+
+    /// Рабочие области всех мониторов в координатах окна рабочего стола
+    pub fn client_areas(&self) -> Vec<LayoutRect> {
+        self.screen_areas().into_iter().map(|area| self.to_client(area)).collect()
+    }
+
+    pub fn icon_cell(&self) -> i32 {
+        BASE_CELL * self.dpi as i32 / BASE_DPI
+    }
+
+    /// Отпечаток конфигурации мониторов: разрешения и расположение,
+    /// например `1920x1080+0+0|2560x1440+1920+0`
+    pub fn fingerprint(&self) -> String {
+        let mut monitors = if self.monitors.is_empty() {
+            vec![self.bounds]
+        } else {
+            self.monitors.clone()
+        };
+        monitors.sort_by_key(|m| (m.left, m.top));
+
+        monitors
+            .iter()
+            .map(|m| format!("{}x{}+{}+{}", m.right - m.left, m.bottom - m.top, m.left, m.top))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    /// Рабочие области мониторов в экранных координатах. В раскладках,
+    /// снятых до учёта мониторов, известен только весь виртуальный экран
+    fn screen_areas(&self) -> Vec<LayoutRect> {
+        if !self.work_areas.is_empty() {
+            self.work_areas.clone()
+        } else if !self.monitors.is_empty() {
+            self.monitors.clone()
+        } else {
+            vec![self.bounds]
+        }
+    }
+
+    /// Рабочая область монитора, на котором лежит точка (или ближайшего к ней)
+    fn screen_area_at(&self, point: (i32, i32)) -> LayoutRect {
+        nearest_area(&self.screen_areas(), point).unwrap_or(self.work_area)
+    }
+
+    fn to_client(&self, rect: LayoutRect) -> LayoutRect {
+        LayoutRect {
+            left: rect.left - self.bounds.left,
+            top: rect.top - self.bounds.top,
+            right: rect.right - self.bounds.left,
+            bottom: rect.bottom - self.bounds.top,
+        }
+    }
+}
+
+impl LayoutRect {
+    fn center(&self) -> (i32, i32) {
+        ((self.left + self.right) / 2, (self.top + self.bottom) / 2)
+    }
+
+    /// Квадрат расстояния от точки до прямоугольника, 0 — точка внутри
+    fn distance_sq(&self, (x, y): (i32, i32)) -> i64 {
+        let dx = (self.left - x).max(x - (self.right - 1)).max(0) as i64;
+        let dy = (self.top - y).max(y - (self.bottom - 1)).max(0) as i64;
+        dx * dx + dy * dy
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn current_display() -> Option<DisplayGeometry> {
+    platform::current_display()
+}
+
+/// Переносит точку со старого дисплея на текущий.
+///
+/// Иконка остаётся на своём мониторе: если он отключён — переезжает на
+/// ближайший к нему из текущих. Внутри монитора она держится ближайшего
+/// края рабочей области: столбец у правого края остаётся у правого края
+/// и на широком мониторе. Отступ от края масштабируется по DPI, результат
+/// не выходит за рабочую область монитора
+pub fn fit_point(x: i32, y: i32, saved: Option<&DisplayGeometry>, current: &DisplayGeometry) -> (i32, i32) {
+    let saved = saved.unwrap_or(current);
+
+    let source_screen = saved.screen_area_at((x + saved.bounds.left, y + saved.bounds.top));
+    let target_screen = current.screen_area_at(source_screen.center());
+
+    let source = saved.to_client(source_screen);
+    let target = current.to_client(target_screen);
+    let cell = current.icon_cell();
+    let scale = (current.dpi.max(1) as i64, saved.dpi.max(1) as i64);
+
+    (
+        fit_axis(x, (source.left, source.right), (target.left, target.right), scale, cell),
+        fit_axis(y, (source.top, source.bottom), (target.top, target.bottom), scale, cell),
+    )
+}
+
+/// Область, в которой лежит точка, или ближайшая к ней
+pub fn nearest_area(areas: &[LayoutRect], point: (i32, i32)) -> Option<LayoutRect> {
+    areas.iter().copied().min_by_key(|area| area.distance_sq(point))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn fit_axis(pos: i32, source: (i32, i32), target: (i32, i32), (num, den): (i64, i64), cell: i32) -> i32 {
+    let scale = |offset: i32| (offset as i64 * num / den) as i32;
+
+    let from_start = pos - source.0;
+    let from_end = source.1 - pos;
+
+    let mapped = if from_start <= from_end {
+        target.0 + scale(from_start)
+    } else {
+        target.1 - scale(from_end)
+    };
+
+    mapped.clamp(target.0, (target.1 - cell).max(target.0))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use super::{DisplayGeometry, LayoutRect};
+    use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO};
+    use windows::Win32::UI::HiDpi::GetDpiForSystem;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, SystemParametersInfoW, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
+        SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SPI_GETWORKAREA, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    pub fn current_display() -> Option<DisplayGeometry> {
+        unsafe {
+            let left = GetSystemMetrics(SM_XVIRTUALSCREEN);
+            let top = GetSystemMetrics(SM_YVIRTUALSCREEN);
+            let width = GetSystemMetrics(SM_CXVIRTUALSCREEN);
+            let height = GetSystemMetrics(SM_CYVIRTUALSCREEN);
+
+            if width <= 0 || height <= 0 {
+                return None;
+            }
+
+            let monitors = monitors();
+
+            let mut work = RECT::default();
+            SystemParametersInfoW(
+                SPI_GETWORKAREA,
+                0,
+                Some(&mut work as *mut RECT as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+            .ok()?;
+
+            Some(DisplayGeometry {
+                bounds: LayoutRect {
+                    left,
+                    top,
+                    right: left + width,
+                    bottom: top + height,
+                },
+                work_area: LayoutRect {
+                    left: work.left,
+                    top: work.top,
+                    right: work.right,
+                    bottom: work.bottom,
+                },
+                dpi: GetDpiForSystem(),
+                monitors: monitors.iter().map(|(monitor, _)| *monitor).collect(),
+                work_areas: monitors.iter().map(|(_, work)| *work).collect(),
+            })
+        }
+    }
+
+    /// Прямоугольник и рабочая область каждого монитора
+    unsafe fn monitors() -> Vec<(LayoutRect, LayoutRect)> {
+        unsafe extern "system" fn callback(monitor: HMONITOR, _: HDC, rect: *mut RECT, lparam: LPARAM) -> BOOL {
+            let monitors = &mut *(lparam.0 as *mut Vec<(LayoutRect, LayoutRect)>);
+            let rect = to_layout_rect(&*rect);
+
+            let mut info = MONITORINFO {
+                cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+                ..Default::default()
+            };
+            let work = if GetMonitorInfoW(monitor, &mut info).as_bool() {
+                to_layout_rect(&info.rcWork)
+            } else {
+                rect
+            };
+
+            monitors.push((rect, work));
+            BOOL(1)
+        }
+
+        let mut monitors: Vec<(LayoutRect, LayoutRect)> = Vec::new();
+        let _ = EnumDisplayMonitors(
+            HDC::default(),
+            None,
+            Some(callback),
+            LPARAM(&mut monitors as *mut _ as isize),
+        );
+        monitors
+    }
+
+    fn to_layout_rect(rect: &RECT) -> LayoutRect {
+        LayoutRect {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::DisplayGeometry;
+
+    pub fn current_display() -> Option<DisplayGeometry> {
+        None
+    }
+}
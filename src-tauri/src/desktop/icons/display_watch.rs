@@ -0,0 +1,68 @@
+//! Повторное применение раскладки иконок при смене конфигурации мониторов
+
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use super::display::current_display;
+use super::{load_icon_positions, restore_icon_positions};
+use crate::config::{get_desktop_folder_path, load_config, CONFIG_LOCK};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Explorer перестраивает рабочий стол не сразу после смены режима
+const SETTLE_DELAY: Duration = Duration::from_millis(1500);
+
+pub fn start_display_watcher(app_handle: AppHandle) {
+    thread::spawn(move || {
+        let mut last = current_display().map(|d| d.fingerprint());
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let Some(fingerprint) = current_display().map(|d| d.fingerprint()) else {
+                continue;
+            };
+
+            if last.as_deref() == Some(fingerprint.as_str()) {
+                continue;
+            }
+
+            // Мониторы ещё переключаются — дождёмся следующего опроса
+            thread::sleep(SETTLE_DELAY);
+            if current_display().map(|d| d.fingerprint()).as_deref() != Some(fingerprint.as_str()) {
+                continue;
+            }
+
+            last = Some(fingerprint.clone());
+
+            match reapply_active_layout() {
+                Ok(true) => {
+                    let _ = app_handle.emit("display-layout-applied", fingerprint);
+                }
+                Ok(false) => {}
+                Err(e) => eprintln!("Не удалось применить раскладку для дисплея: {}", e),
+            }
+        }
+    });
+}
+
+fn reapply_active_layout() -> Result<bool, String> {
+    // Не пересекаемся с переключением стола
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let config = load_config();
+
+    if config.active_desktop_id <= 0 {
+        return Ok(false);
+    }
+
+    let path = get_desktop_folder_path(config.active_desktop_id);
+    let layout = load_icon_positions(&path);
+
+    if layout.icons.is_empty() {
+        return Ok(false);
+    }
+
+    restore_icon_positions(&layout, &path)?;
+    Ok(true)
+}
@@ -0,0 +1,227 @@
+//! Сетка ячеек для расстановки иконок
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::display::{nearest_area, DisplayGeometry, LayoutRect};
+use super::layout::IconPosition;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Corner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Grid {
+    pub cell_width: i32,
+    pub cell_height: i32,
+}
+
+impl Grid {
+    /// Оценка по DPI, если спросить Explorer и систему не получилось
+    pub fn for_display(display: &DisplayGeometry) -> Grid {
+        let cell = display.icon_cell();
+        Grid {
+            cell_width: cell,
+            cell_height: cell,
+        }
+    }
+
+    /// Ближайшая ячейка области (по столбцу и строке)
+    fn nearest_cell(&self, area: LayoutRect, (x, y): (i32, i32)) -> (i32, i32) {
+        let (columns, rows) = self.dimensions(area);
+        let column = div_round(x - area.left, self.cell_width).clamp(0, (columns - 1).max(0));
+        let row = div_round(y - area.top, self.cell_height).clamp(0, (rows - 1).max(0));
+        (column, row)
+    }
+
+    fn dimensions(&self, area: LayoutRect) -> (i32, i32) {
+        (
+            ((area.right - area.left) / self.cell_width).max(1),
+            ((area.bottom - area.top) / self.cell_height).max(1),
+        )
+    }
+
+    fn cell_origin(&self, area: LayoutRect, (column, row): (i32, i32)) -> (i32, i32) {
+        (area.left + column * self.cell_width, area.top + row * self.cell_height)
+    }
+
+    /// Ячейки области по столбцам, как их заполняет Explorer, начиная от угла
+    pub fn cells(&self, area: LayoutRect, corner: Corner) -> Vec<(i32, i32)> {
+        let columns = ((area.right - area.left) / self.cell_width).max(0);
+        let rows = ((area.bottom - area.top) / self.cell_height).max(0);
+        let from_right = matches!(corner, Corner::TopRight | Corner::BottomRight);
+        let from_bottom = matches!(corner, Corner::BottomLeft | Corner::BottomRight);
+
+        let mut cells = Vec::with_capacity((columns * rows) as usize);
+
+        for column in 0..columns {
+            let x = if from_right {
+                area.right - (column + 1) * self.cell_width
+            } else {
+                area.left + column * self.cell_width
+            };
+
+            for row in 0..rows {
+                let y = if from_bottom {
+                    area.bottom - (row + 1) * self.cell_height
+                } else {
+                    area.top + row * self.cell_height
+                };
+                cells.push((x, y));
+            }
+        }
+
+        cells
+    }
+
+    /// Ячейка свободна, если ни одна иконка не заходит в неё больше чем на полячейки
+    pub fn is_free(&self, occupied: &[(i32, i32)], (x, y): (i32, i32)) -> bool {
+        occupied.iter().all(|&(ox, oy)| {
+            (ox - x).abs() * 2 >= self.cell_width || (oy - y).abs() * 2 >= self.cell_height
+        })
+    }
+
+    /// Первая свободная ячейка области; найденная ячейка сразу помечается занятой
+    pub fn take_free_cell(
+        &self,
+        area: LayoutRect,
+        corner: Corner,
+        occupied: &mut Vec<(i32, i32)>,
+    ) -> Option<(i32, i32)> {
+        let cell = self
+            .cells(area, corner)
+            .into_iter()
+            .find(|&cell| self.is_free(occupied, cell))?;
+
+        occupied.push(cell);
+        Some(cell)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Сетка рабочего стола в том виде, в каком её сейчас использует Explorer
+pub fn current_grid(display: &DisplayGeometry) -> Grid {
+    platform::icon_spacing(display.dpi)
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .map(|(cell_width, cell_height)| Grid { cell_width, cell_height })
+        .unwrap_or_else(|| Grid::for_display(display))
+}
+
+/// Привязывает позиции к ближайшим ячейкам без наложений.
+///
+/// Каждая иконка привязывается к сетке монитора, на котором лежит
+/// (`areas` — рабочие области мониторов). Первыми ячейки занимают иконки,
+/// которые ближе всего к своей ячейке; проигравшие уходят в ближайшую
+/// свободную на том же мониторе. Если свободных не осталось, иконка
+/// остаётся в своей ячейке поверх другой
+pub fn snap_positions(
+    grid: &Grid,
+    areas: &[LayoutRect],
+    icons: &HashMap<String, IconPosition>,
+) -> Vec<(String, IconPosition)> {
+    let mut order: Vec<_> = icons
+        .iter()
+        .filter_map(|(name, pos)| {
+            let area = nearest_area(areas, (pos.x, pos.y))?;
+            let cell = grid.nearest_cell(area, (pos.x, pos.y));
+            let distance = distance_sq(grid.cell_origin(area, cell), (pos.x, pos.y));
+            Some((name, pos, area, cell, distance))
+        })
+        .collect();
+    order.sort_by(|a, b| a.4.cmp(&b.4).then_with(|| a.0.cmp(b.0)));
+
+    // Ячейки разных мониторов различаем по их экранной позиции
+    let mut taken: HashSet<(i32, i32)> = HashSet::new();
+
+    order
+        .into_iter()
+        .map(|(name, pos, area, cell, _)| {
+            let cell = if taken.contains(&grid.cell_origin(area, cell)) {
+                nearest_free_cell(grid, area, cell, (pos.x, pos.y), &taken).unwrap_or(cell)
+            } else {
+                cell
+            };
+
+            let (x, y) = grid.cell_origin(area, cell);
+            taken.insert((x, y));
+            (name.clone(), IconPosition { x, y })
+        })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Деление с округлением к ближайшему (в том числе для отрицательных)
+fn div_round(value: i32, divisor: i32) -> i32 {
+    (value as f64 / divisor as f64).round() as i32
+}
+
+fn distance_sq(a: (i32, i32), b: (i32, i32)) -> i64 {
+    let dx = (a.0 - b.0) as i64;
+    let dy = (a.1 - b.1) as i64;
+    dx * dx + dy * dy
+}
+
+/// Обходит кольца ячеек вокруг занятой и берёт ближайшую к исходной точке
+fn nearest_free_cell(
+    grid: &Grid,
+    area: LayoutRect,
+    (column, row): (i32, i32),
+    point: (i32, i32),
+    taken: &HashSet<(i32, i32)>,
+) -> Option<(i32, i32)> {
+    let (columns, rows) = grid.dimensions(area);
+
+    for ring in 1..columns.max(rows) {
+        let best = (-ring..=ring)
+            .flat_map(|dc| (-ring..=ring).map(move |dr| (column + dc, row + dr)))
+            .filter(|&(c, r)| (c - column).abs() == ring || (r - row).abs() == ring)
+            .filter(|&(c, r)| c >= 0 && r >= 0 && c < columns && r < rows)
+            .filter(|&cell| !taken.contains(&grid.cell_origin(area, cell)))
+            .min_by_key(|&cell| distance_sq(grid.cell_origin(area, cell), point));
+
+        if best.is_some() {
+            return best;
+        }
+    }
+
+    None
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use super::super::com;
+    use windows::Win32::UI::HiDpi::GetSystemMetricsForDpi;
+    use windows::Win32::UI::WindowsAndMessaging::{SM_CXICONSPACING, SM_CYICONSPACING};
+
+    /// Шаг сетки из окна рабочего стола, иначе — из системных метрик
+    pub fn icon_spacing(dpi: u32) -> Option<(i32, i32)> {
+        com::icon_spacing().ok().or_else(|| unsafe {
+            let width = GetSystemMetricsForDpi(SM_CXICONSPACING, dpi);
+            let height = GetSystemMetricsForDpi(SM_CYICONSPACING, dpi);
+            (width > 0 && height > 0).then_some((width, height))
+        })
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn icon_spacing(_: u32) -> Option<(i32, i32)> {
+        None
+    }
+}
@@ -0,0 +1,99 @@
+//! История раскладок иконок стола для отката
+
+use super::layout::DesktopIconsLayout;
+use super::storage::{history_file, write_store_file};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAX_VERSIONS: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LayoutVersion {
+    pub id: u64,
+    /// Когда раскладка была вытеснена новой, секунды Unix
+    pub saved_at: u64,
+    pub layout: DesktopIconsLayout,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LayoutVersionSummary {
+    pub id: u64,
+    pub saved_at: u64,
+    pub icon_count: usize,
+}
+
+/// Версии от новых к старым
+pub fn list_versions(desktop_path: &Path) -> Vec<LayoutVersionSummary> {
+    read_history(desktop_path)
+        .iter()
+        .rev()
+        .map(|v| LayoutVersionSummary {
+            id: v.id,
+            saved_at: v.saved_at,
+            icon_count: v.layout.icons.len(),
+        })
+        .collect()
+}
+
+pub fn get_version(desktop_path: &Path, id: u64) -> Option<DesktopIconsLayout> {
+    read_history(desktop_path)
+        .into_iter()
+        .find(|v| v.id == id)
+        .map(|v| v.layout)
+}
+
+pub fn push_version(desktop_path: &Path, layout: DesktopIconsLayout) -> Result<(), String> {
+    let mut history = read_history(desktop_path);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let mut id = now.as_millis() as u64;
+    if let Some(last) = history.last() {
+        id = id.max(last.id + 1);
+    }
+
+    history.push(LayoutVersion {
+        id,
+        saved_at: now.as_secs(),
+        layout,
+    });
+
+    if history.len() > MAX_VERSIONS {
+        history.drain(..history.len() - MAX_VERSIONS);
+    }
+
+    write_history(desktop_path, &history)
+}
+
+/// Забирает самую свежую версию из истории
+pub fn pop_version(desktop_path: &Path) -> Result<Option<DesktopIconsLayout>, String> {
+    let mut history = read_history(desktop_path);
+    let Some(version) = history.pop() else {
+        return Ok(None);
+    };
+
+    write_history(desktop_path, &history)?;
+    Ok(Some(version.layout))
+}
+
+/// Вся история, от старых версий к новым
+pub fn all_versions(desktop_path: &Path) -> Vec<LayoutVersion> {
+    read_history(desktop_path)
+}
+
+fn read_history(desktop_path: &Path) -> Vec<LayoutVersion> {
+    fs::read_to_string(history_file(desktop_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_history(desktop_path: &Path, history: &[LayoutVersion]) -> Result<(), String> {
+    let content = serde_json::to_string(history).map_err(|e| e.to_string())?;
+
+    write_store_file(&history_file(desktop_path), &content)
+        .map_err(|e| format!("Не удалось сохранить историю раскладок: {}", e))
+}
@@ -0,0 +1,165 @@
+//! Идентичность файлов, чтобы раскладка пережила их переименование
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::diff::desktop_files;
+use super::layout::DesktopIconsLayout;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct FileIdentity {
+    /// Серийный номер тома и индекс файла NTFS, `XXXXXXXX:XXXXXXXXXXXXXXXX`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+    pub size: u64,
+    /// Время создания, миллисекунды Unix
+    pub created: u64,
+}
+
+impl FileIdentity {
+    pub fn matches(&self, other: &FileIdentity) -> bool {
+        match (&self.file_id, &other.file_id) {
+            (Some(a), Some(b)) => a == b,
+            // Запасной вариант: без времени создания совпадение по размеру ненадёжно
+            _ => self.created != 0 && self.size == other.size && self.created == other.created,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn file_identity(path: &Path) -> Option<FileIdentity> {
+    let metadata = fs::metadata(path).ok()?;
+
+    let created = metadata
+        .created()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    Some(FileIdentity {
+        file_id: platform::file_id(path),
+        size: if metadata.is_dir() { 0 } else { metadata.len() },
+        created,
+    })
+}
+
+/// Идентичности файлов раскладки. Для файлов, которых уже нет в папке,
+/// остаётся ранее записанная идентичность
+pub fn collect_identities(layout: &DesktopIconsLayout, desktop_path: &Path) -> HashMap<String, FileIdentity> {
+    layout
+        .icons
+        .keys()
+        .filter(|name| !name.starts_with("::"))
+        .filter_map(|name| {
+            let identity = file_identity(&desktop_path.join(name))
+                .or_else(|| layout.identities.get(name).cloned())?;
+            Some((name.clone(), identity))
+        })
+        .collect()
+}
+
+/// Записи, чьих файлов больше нет, переносит на переименованные файлы.
+/// `None` — переименований не найдено
+pub fn follow_renames(layout: &DesktopIconsLayout, desktop_path: &Path) -> Option<DesktopIconsLayout> {
+    let files = desktop_files(desktop_path);
+
+    let mut unclaimed: Vec<(String, Option<FileIdentity>)> = files
+        .iter()
+        .filter(|name| !layout.icons.contains_key(*name))
+        .map(|name| (name.clone(), file_identity(&desktop_path.join(name))))
+        .collect();
+
+    if unclaimed.is_empty() {
+        return None;
+    }
+
+    let mut updated = layout.clone();
+    let mut renamed = false;
+
+    for (old_name, position) in &layout.icons {
+        if old_name.starts_with("::") || files.contains(old_name) {
+            continue;
+        }
+
+        let Some(identity) = layout.identities.get(old_name) else {
+            continue;
+        };
+
+        let Some(found) = unclaimed
+            .iter()
+            .position(|(_, candidate)| candidate.as_ref().is_some_and(|c| c.matches(identity)))
+        else {
+            continue;
+        };
+
+        let (new_name, new_identity) = unclaimed.swap_remove(found);
+
+        updated.icons.remove(old_name);
+        updated.identities.remove(old_name);
+        updated.icons.insert(new_name.clone(), position.clone());
+        if let Some(new_identity) = new_identity {
+            updated.identities.insert(new_name, new_identity);
+        }
+        renamed = true;
+    }
+
+    renamed.then_some(updated)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Storage::FileSystem::{
+        CreateFileW, GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS,
+        FILE_READ_ATTRIBUTES, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+
+    pub fn file_id(path: &Path) -> Option<String> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+
+        unsafe {
+            // Папки открываются только с FILE_FLAG_BACKUP_SEMANTICS
+            let handle = CreateFileW(
+                PCWSTR(wide.as_ptr()),
+                FILE_READ_ATTRIBUTES.0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAG_BACKUP_SEMANTICS,
+                HANDLE::default(),
+            )
+            .ok()?;
+
+            let mut info = BY_HANDLE_FILE_INFORMATION::default();
+            let result = GetFileInformationByHandle(handle, &mut info);
+            let _ = CloseHandle(handle);
+            result.ok()?;
+
+            let index = ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64;
+            Some(format!("{:08X}:{:016X}", info.dwVolumeSerialNumber, index))
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::path::Path;
+
+    pub fn file_id(_: &Path) -> Option<String> {
+        None
+    }
+}
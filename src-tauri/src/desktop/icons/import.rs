@@ -0,0 +1,369 @@
+//! Импорт раскладок иконок из реестра Explorer и файлов других программ
+
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use super::display::current_display;
+use super::layout::{DesktopIconsLayout, IconPosition};
+use super::storage::LayoutExport;
+
+/// Размер заголовка значения ItemPos
+const ITEM_POS_HEADER: usize = 0x10;
+/// Подпись блока расширения с длинным именем файла
+const FILE_EXTENSION_SIGNATURE: u32 = 0xBEEF_0004;
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImportSource {
+    /// Текущие позиции из реестра Explorer
+    Explorer,
+    /// Файл раскладки другой программы или выгрузка Kimi
+    File { path: String },
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn import_layout(source: &ImportSource) -> Result<DesktopIconsLayout, String> {
+    let layout = match source {
+        ImportSource::Explorer => import_explorer()?,
+        ImportSource::File { path } => {
+            let content = fs::read_to_string(Path::new(path))
+                .map_err(|e| format!("Не удалось прочитать файл раскладки: {}", e))?;
+            parse_layout_file(&content)?
+        }
+    };
+
+    if layout.icons.is_empty() {
+        return Err("В источнике нет ни одной позиции иконок".to_string());
+    }
+
+    Ok(layout)
+}
+
+/// Файл раскладки: выгрузка Kimi, coords.kimi или CSV
+pub fn parse_layout_file(content: &str) -> Result<DesktopIconsLayout, String> {
+    if let Ok(export) = serde_json::from_str::<LayoutExport>(content) {
+        return Ok(export.layouts.into_latest());
+    }
+
+    if let Ok(layout) = serde_json::from_str::<DesktopIconsLayout>(content) {
+        return Ok(layout);
+    }
+
+    let icons = parse_csv_layout(content);
+    if icons.is_empty() {
+        return Err("Формат файла раскладки не распознан".to_string());
+    }
+
+    Ok(DesktopIconsLayout {
+        icons: icons.into_iter().collect(),
+        ..Default::default()
+    })
+}
+
+/// Строки `имя;x;y` (разделитель — запятая, точка с запятой или табуляция).
+/// Координаты берутся с конца строки, так что запятые в имени не мешают.
+/// Строки без чисел (заголовок, комментарии) пропускаются
+pub fn parse_csv_layout(content: &str) -> Vec<(String, IconPosition)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let separator = [';', '\t', ','].into_iter().find(|s| line.contains(*s))?;
+
+            let mut fields = line.rsplitn(3, separator);
+            let y = parse_coordinate(fields.next()?)?;
+            let x = parse_coordinate(fields.next()?)?;
+            let name = fields.next()?.trim().trim_matches('"').replace("\"\"", "\"");
+
+            (!name.is_empty()).then_some((name, IconPosition { x, y }))
+        })
+        .collect()
+}
+
+/// Разбирает значение ItemPos из `Shell\Bags\1\Desktop`.
+///
+/// Формат не документирован. После заголовка идут записи: элемент
+/// списка идентификаторов оболочки (SHITEMID, первые два байта — его
+/// размер) и за ним координаты x, y по четыре байта. Из элемента файла
+/// берётся длинное имя из блока расширения, иначе короткое 8.3.
+/// Непонятные элементы (ярлыки оболочки вроде «Корзины») пропускаются.
+///
+/// Разбор не сверен с данными, снятыми с реального Explorer: тесты проверяют
+/// его только на синтетическом образце, собранном по этому же описанию
+pub fn parse_item_pos(data: &[u8]) -> Vec<(String, IconPosition)> {
+    let mut icons = Vec::new();
+    let mut offset = ITEM_POS_HEADER;
+
+    while let Some(size) = read_u16(data, offset).map(usize::from) {
+        if size < 2 || offset + size + 8 > data.len() {
+            break;
+        }
+
+        let item = &data[offset..offset + size];
+        let x = read_i32(data, offset + size);
+        let y = read_i32(data, offset + size + 4);
+
+        if let (Some(name), Some(x), Some(y)) = (file_item_name(item), x, y) {
+            icons.push((name, IconPosition { x, y }));
+        }
+
+        offset += size + 8;
+    }
+
+    icons
+}
+
+/// Разрешение из имени значения `ItemPos1920x1080x96(1)`
+pub fn item_pos_resolution(value_name: &str) -> Option<(i32, i32)> {
+    let rest = value_name.strip_prefix("ItemPos")?;
+    let mut parts = rest.split(|c: char| !c.is_ascii_digit()).filter(|p| !p.is_empty());
+
+    let width = parts.next()?.parse().ok()?;
+    let height = parts.next()?.parse().ok()?;
+    Some((width, height))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Из всех значений ItemPos выбираем снятое при текущем разрешении;
+/// если такого нет — самое крупное. Позиции Explorer относятся к экрану,
+/// на котором сняты, так что дисплей указываем только при совпадении
+fn import_explorer() -> Result<DesktopIconsLayout, String> {
+    let values = platform::read_item_pos_values()?;
+    let display = current_display();
+    let current = display.as_ref().map(|d| {
+        (d.bounds.right - d.bounds.left, d.bounds.bottom - d.bounds.top)
+    });
+
+    let chosen = values
+        .iter()
+        .filter_map(|(name, data)| item_pos_resolution(name).map(|res| (res, data)))
+        .max_by_key(|((w, h), _)| (Some((*w, *h)) == current, *w as i64 * *h as i64))
+        .ok_or("Explorer не сохранил позиции иконок рабочего стола")?;
+
+    let ((width, height), data) = chosen;
+
+    Ok(DesktopIconsLayout {
+        icons: parse_item_pos(data).into_iter().collect(),
+        display: display.filter(|_| current == Some((width, height))),
+        ..Default::default()
+    })
+}
+
+fn parse_coordinate(value: &str) -> Option<i32> {
+    value.trim().trim_matches('"').parse().ok()
+}
+
+/// Имя из элемента файла или папки (тип 0x3X)
+fn file_item_name(item: &[u8]) -> Option<String> {
+    let kind = *item.get(2)?;
+    if kind & 0x70 != 0x30 {
+        return None;
+    }
+
+    // cb(2) тип(1) ?(1) размер(4) дата и время(4) атрибуты(2) → короткое имя
+    let short_start = 14;
+    let short_len = item.get(short_start..)?.iter().position(|b| *b == 0)?;
+    let short_name = String::from_utf8_lossy(&item[short_start..short_start + short_len]).into_owned();
+
+    // Короткое имя выровнено по двум байтам, за ним блоки расширения
+    let mut offset = short_start + short_len + 1;
+    offset += offset % 2;
+
+    let long_name = item.get(offset..).and_then(extension_long_name);
+    let name = long_name.unwrap_or(short_name);
+
+    (!name.is_empty()).then_some(name)
+}
+
+/// Длинное имя из блока расширения 0xBEEF0004. Смещение имени зависит
+/// от версии блока: в новых версиях перед ним ссылка MFT и служебные поля
+fn extension_long_name(block: &[u8]) -> Option<String> {
+    let size = usize::from(read_u16(block, 0)?);
+    let version = read_u16(block, 2)?;
+
+    if read_u32(block, 4)? != FILE_EXTENSION_SIGNATURE || size > block.len() {
+        return None;
+    }
+
+    let mut offset = 18;
+    if version >= 7 {
+        offset += 2 + 8 + 8;
+    }
+    if version >= 3 {
+        offset += 2;
+    }
+    if version >= 9 {
+        offset += 4;
+    }
+    if version >= 8 {
+        offset += 4;
+    }
+
+    let units: Vec<u16> = block
+        .get(offset..size)?
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0)
+        .collect();
+
+    String::from_utf16(&units).ok().filter(|n| !n.is_empty())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Option<i32> {
+    read_u32(data, offset).map(|v| v as i32)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use winreg::enums::*;
+    use winreg::RegKey;
+
+    const REG_PATH: &str = "Software\\Microsoft\\Windows\\Shell\\Bags\\1\\Desktop";
+
+    /// Имена и данные значений ItemPos из `Shell\Bags\1\Desktop`
+    pub fn read_item_pos_values() -> Result<Vec<(String, Vec<u8>)>, String> {
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+
+        let key = hkcu
+            .open_subkey(REG_PATH)
+            .map_err(|e| format!("Не удалось открыть реестр: {}", e))?;
+
+        Ok(key
+            .enum_values()
+            .filter_map(Result::ok)
+            .filter(|(name, value)| value.vtype == REG_BINARY && name.starts_with("ItemPos"))
+            .map(|(name, value)| (name, value.bytes))
+            .collect())
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn read_item_pos_values() -> Result<Vec<(String, Vec<u8>)>, String> {
+        Err("Только для Windows".to_string())
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/import/", $name)
+        };
+    }
+
+    fn position(icons: &[(String, IconPosition)], name: &str) -> Option<(i32, i32)> {
+        icons.iter().find(|(n, _)| n == name).map(|(_, p)| (p.x, p.y))
+    }
+
+    // item_pos_synthetic.bin собран вручную по описанию у `parse_item_pos`:
+    // заголовок, элементы файла с блоком расширения v9 и без него, элемент
+    // папки и «Этот компьютер». Это проверка согласованности разбора, а не
+    // совместимости с Explorer — для неё нужен образец с реальной системы
+    #[test]
+    fn item_pos_reads_names_and_coordinates() {
+        let icons = parse_item_pos(include_bytes!(fixture!("item_pos_synthetic.bin")));
+
+        assert_eq!(icons.len(), 3);
+        assert_eq!(position(&icons, "Отчёт 2024.docx"), Some((20, 2)));
+        assert_eq!(position(&icons, "NOTES.TXT"), Some((1860, 2)));
+        assert_eq!(position(&icons, "Проекты, архив"), Some((-1900, 210)));
+    }
+
+    #[test]
+    fn item_pos_skips_shell_items() {
+        let icons = parse_item_pos(include_bytes!(fixture!("item_pos_synthetic.bin")));
+        assert!(icons.iter().all(|(name, _)| !name.is_empty() && !name.starts_with("::")));
+    }
+
+    #[test]
+    fn item_pos_stops_on_truncated_data() {
+        let data = include_bytes!(fixture!("item_pos_synthetic.bin"));
+
+        for len in 0..data.len() {
+            let icons = parse_item_pos(&data[..len]);
+            assert!(icons.len() <= 3);
+        }
+    }
+
+    #[test]
+    fn item_pos_resolution_from_value_name() {
+        assert_eq!(item_pos_resolution("ItemPos1920x1080x96(1)"), Some((1920, 1080)));
+        assert_eq!(item_pos_resolution("ItemPos3840x1080(1)"), Some((3840, 1080)));
+        assert_eq!(item_pos_resolution("IconLayouts"), None);
+    }
+
+    #[test]
+    fn csv_skips_header_and_keeps_commas_in_names() {
+        let icons = parse_csv_layout(include_str!(fixture!("layout.csv")));
+
+        assert_eq!(icons.len(), 3);
+        assert_eq!(position(&icons, "report.docx"), Some((20, 2)));
+        assert_eq!(position(&icons, "Проекты, архив"), Some((-1900, 210)));
+        assert_eq!(position(&icons, "notes.txt"), Some((1860, 2)));
+    }
+
+    #[test]
+    fn csv_with_semicolons() {
+        let icons = parse_csv_layout(include_str!(fixture!("layout_semicolon.csv")));
+
+        assert_eq!(icons.len(), 2);
+        assert_eq!(position(&icons, "setup, v2.exe"), Some((96, 2)));
+    }
+
+    #[test]
+    fn layout_file_detects_kimi_export() {
+        let layout = parse_layout_file(include_str!(fixture!("export.json"))).unwrap();
+
+        assert_eq!(layout.icons.len(), 2);
+        assert_eq!(layout.icons["Проекты, архив"], IconPosition { x: 20, y: 106 });
+    }
+
+    #[test]
+    fn layout_file_detects_coords_kimi() {
+        let layout = parse_layout_file(include_str!(fixture!("coords.kimi"))).unwrap();
+
+        assert_eq!(layout.icons.len(), 2);
+        assert_eq!(layout.icons["notes.txt"], IconPosition { x: 1860, y: 2 });
+        assert_eq!(layout.display.map(|d| d.dpi), Some(96));
+    }
+
+    #[test]
+    fn layout_file_falls_back_to_csv() {
+        let layout = parse_layout_file(include_str!(fixture!("layout.csv"))).unwrap();
+        assert_eq!(layout.icons.len(), 3);
+    }
+
+    #[test]
+    fn layout_file_rejects_unknown_format() {
+        assert!(parse_layout_file("не раскладка").is_err());
+        assert!(parse_layout_file("{\"desktops\": []}").is_err());
+    }
+}
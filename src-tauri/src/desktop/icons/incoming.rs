@@ -0,0 +1,92 @@
+//! Наблюдатель за новыми файлами активного стола
+
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+
+use super::diff::desktop_files;
+use super::display::current_display;
+use super::grid::current_grid;
+use super::policy::{load_policy, place_new_icons};
+use super::{get_current_icon_positions, load_icon_positions, position_icons};
+use crate::config::{get_desktop_folder_path, load_config, CONFIG_LOCK};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+struct WatchState {
+    desktop_id: i32,
+    known: HashSet<String>,
+    pending: Vec<String>,
+}
+
+pub fn start_incoming_watcher() {
+    thread::spawn(|| {
+        let mut state = WatchState::default();
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if let Err(e) = tick(&mut state) {
+                eprintln!("Размещение новых иконок: {}", e);
+            }
+        }
+    });
+}
+
+fn tick(state: &mut WatchState) -> Result<(), String> {
+    // Во время переключения папка и иконки на экране не совпадают
+    let Ok(_lock) = CONFIG_LOCK.try_lock() else {
+        return Ok(());
+    };
+
+    let desktop_id = load_config().active_desktop_id;
+    let path = get_desktop_folder_path(desktop_id);
+    let files = desktop_files(&path);
+
+    // Сменился стол — начинаем со свежего снимка
+    if desktop_id != state.desktop_id {
+        *state = WatchState {
+            desktop_id,
+            known: files,
+            pending: Vec::new(),
+        };
+        return Ok(());
+    }
+
+    let ready: Vec<String> = std::mem::take(&mut state.pending)
+        .into_iter()
+        .filter(|name| files.contains(name))
+        .collect();
+
+    state.pending = files.difference(&state.known).cloned().collect();
+    state.known = files;
+
+    if desktop_id <= 0 || ready.is_empty() {
+        return Ok(());
+    }
+
+    let policy = load_policy(desktop_id);
+    if policy.has_no_rules() {
+        return Ok(());
+    }
+
+    let Some(display) = current_display() else {
+        return Ok(());
+    };
+
+    // Файл с именем из раскладки (например, восстановленный) займёт своё место сам
+    let saved = load_icon_positions(&path);
+    let new_names: Vec<String> = ready.into_iter().filter(|n| !saved.icons.contains_key(n)).collect();
+
+    let live = get_current_icon_positions()?;
+    let mut occupied: Vec<(i32, i32)> = live
+        .icons
+        .iter()
+        .filter(|(name, _)| !new_names.contains(name))
+        .map(|(_, p)| (p.x, p.y))
+        .collect();
+
+    let grid = current_grid(&display);
+    position_icons(&place_new_icons(&policy, &new_names, &mut occupied, &display, &grid))
+}
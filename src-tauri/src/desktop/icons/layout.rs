@@ -3,7 +3,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use super::display::{fit_point, DisplayGeometry};
+use super::identity::FileIdentity;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct IconPosition {
     pub x: i32,
     pub y: i32,
@@ -12,4 +15,35 @@ pub struct IconPosition {
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct DesktopIconsLayout {
     pub icons: HashMap<String, IconPosition>,
+    /// Дисплей, на котором сняты позиции. В старых coords.kimi его нет
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<DisplayGeometry>,
+    /// Идентичность файлов по имени — чтобы найти их после переименования
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub identities: HashMap<String, FileIdentity>,
+}
+
+impl DesktopIconsLayout {
+    /// Совпадают ли позиции иконок (дисплей не учитывается)
+    pub fn same_positions(&self, other: &DesktopIconsLayout) -> bool {
+        self.icons == other.icons
+    }
+
+    /// Копия раскладки, подогнанная под текущий дисплей
+    pub fn fitted_to(&self, current: &DisplayGeometry) -> DesktopIconsLayout {
+        let icons = self
+            .icons
+            .iter()
+            .map(|(name, pos)| {
+                let (x, y) = fit_point(pos.x, pos.y, self.display.as_ref(), current);
+                (name.clone(), IconPosition { x, y })
+            })
+            .collect();
+
+        DesktopIconsLayout {
+            icons,
+            display: Some(current.clone()),
+            identities: self.identities.clone(),
+        }
+    }
 }
@@ -1,25 +1,56 @@
 //! Управление позициями иконок рабочего стола
 
+mod autosave;
 mod com;
+mod diff;
+mod display;
+mod display_watch;
+mod grid;
+mod history;
+mod identity;
+mod import;
+mod incoming;
 mod layout;
+mod organize;
+mod policy;
+mod report;
 mod storage;
+mod zones;
 
+use display::current_display;
+use grid::current_grid;
+
+pub use autosave::start_icon_autosave;
+pub use diff::{LayoutDiff, LayoutSource};
+pub use display_watch::start_display_watcher;
+pub use history::LayoutVersionSummary;
+pub use import::ImportSource;
+pub use incoming::start_incoming_watcher;
 pub use layout::DesktopIconsLayout;
-pub use storage::{load_icon_positions, save_icon_positions};
+pub use organize::{OrganizeOptions, OrganizePreview};
+pub use policy::DesktopIconPolicy;
+pub use report::{init_restore_reports, RestoreReport};
+pub use storage::{load_icon_positions, migrate_legacy_layouts, save_icon_positions, DisplayLayoutSummary};
+pub use zones::ZonePreview;
 
 #[cfg(windows)]
-pub use com::{get_current_icon_positions, restore_icon_positions};
+pub use com::{get_current_icon_positions, position_icons, restore_icon_positions};
 
 #[cfg(not(windows))]
 pub fn get_current_icon_positions() -> Result<DesktopIconsLayout, String> {
     Err("Только для Windows".to_string())
 }
 
+#[cfg(not(windows))]
+pub fn position_icons(_icons: &[(String, layout::IconPosition)]) -> Result<(), String> {
+    Err("Только для Windows".to_string())
+}
+
 #[cfg(not(windows))]
 pub fn restore_icon_positions(
     _layout: &DesktopIconsLayout,
-    _desktop_path: &std::path::PathBuf,
-) -> Result<(), String> {
+    _desktop_path: &std::path::Path,
+) -> Result<RestoreReport, String> {
     Err("Только для Windows".to_string())
 }
 
@@ -27,7 +58,7 @@ pub fn restore_icon_positions(
 // Tauri Commands
 // ─────────────────────────────────────────────────────────────────────────────
 
-use crate::config::get_desktop_folder_path;
+use crate::config::{get_desktop_folder_path, load_config, save_config, IconAutosaveSettings, CONFIG_LOCK};
 
 #[tauri::command]
 pub async fn save_desktop_icons(desktop_id: i32) -> Result<(), String> {
@@ -38,14 +69,18 @@ pub async fn save_desktop_icons(desktop_id: i32) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn restore_desktop_icons(desktop_id: i32) -> Result<(), String> {
+pub async fn restore_desktop_icons(desktop_id: i32) -> Result<RestoreReport, String> {
     let path = get_desktop_folder_path(desktop_id);
     let layout = load_icon_positions(&path);
-    
-    if !layout.icons.is_empty() {
-        restore_icon_positions(&layout, &path)?;
-    }
-    Ok(())
+
+    restore_icon_positions(&layout, &path)
+}
+
+/// Отчёт последнего восстановления раскладки — при переключении стола,
+/// смене мониторов или откате
+#[tauri::command]
+pub async fn get_last_restore_report() -> Result<Option<RestoreReport>, String> {
+    Ok(report::last_report())
 }
 
 #[tauri::command]
@@ -54,12 +89,157 @@ pub async fn get_saved_icon_positions(desktop_id: i32) -> Result<DesktopIconsLay
     Ok(load_icon_positions(&path))
 }
 
+/// Раскладки стола по конфигурациям мониторов
+#[tauri::command]
+pub async fn get_display_layouts(desktop_id: i32) -> Result<Vec<DisplayLayoutSummary>, String> {
+    let path = get_desktop_folder_path(desktop_id);
+    Ok(storage::list_display_layouts(&path))
+}
+
+#[tauri::command]
+pub async fn delete_display_layout(desktop_id: i32, fingerprint: String) -> Result<(), String> {
+    let path = get_desktop_folder_path(desktop_id);
+    storage::remove_display_layout(&path, &fingerprint)
+}
+
+/// Выгружает раскладки и историю стола в выбранный файл
+#[tauri::command]
+pub async fn export_icon_layouts(desktop_id: i32, target_path: String) -> Result<(), String> {
+    let config = load_config();
+    let desktop = config
+        .desktops
+        .iter()
+        .find(|d| d.id == desktop_id)
+        .ok_or("Рабочий стол Kimi не найден")?;
+
+    storage::export_layouts(desktop_id, &desktop.name, std::path::Path::new(&target_path))
+}
+
+/// Раскладка из Explorer или файла другой программы — без сохранения
+#[tauri::command]
+pub async fn preview_icon_import(source: ImportSource) -> Result<DesktopIconsLayout, String> {
+    import::import_layout(&source)
+}
+
+/// Сохраняет импортированную раскладку в стол Kimi; прежняя уходит в историю
+#[tauri::command]
+pub async fn import_icon_layout(desktop_id: i32, source: ImportSource) -> Result<DesktopIconsLayout, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+
+    if !load_config().desktops.iter().any(|d| d.id == desktop_id) {
+        return Err("Рабочий стол Kimi не найден".to_string());
+    }
+
+    let layout = import::import_layout(&source)?;
+    apply_saved_layout(desktop_id, &layout, true)?;
+    Ok(layout)
+}
+
 #[tauri::command]
 pub async fn force_save_current_layout(desktop_id: i32) -> Result<(), String> {
     std::thread::sleep(std::time::Duration::from_millis(200));
     save_desktop_icons(desktop_id).await
 }
 
+/// Прежние раскладки стола, от новых к старым
+#[tauri::command]
+pub async fn list_layout_versions(desktop_id: i32) -> Result<Vec<LayoutVersionSummary>, String> {
+    let path = get_desktop_folder_path(desktop_id);
+    Ok(history::list_versions(&path))
+}
+
+/// Делает версию из истории текущей раскладкой; вытесненная уходит в историю
+#[tauri::command]
+pub async fn restore_layout_version(desktop_id: i32, version_id: u64) -> Result<(), String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = get_desktop_folder_path(desktop_id);
+
+    let layout = history::get_version(&path, version_id).ok_or("Версия раскладки не найдена")?;
+    apply_saved_layout(desktop_id, &layout, true)
+}
+
+#[tauri::command]
+pub async fn undo_layout_change(desktop_id: Option<i32>) -> Result<bool, String> {
+    undo_last_layout_change(desktop_id)
+}
+
+/// Сохранённая раскладка против живой: что сдвинулось, чего нет на диске
+#[tauri::command]
+pub async fn diff_live_layout(desktop_id: i32) -> Result<LayoutDiff, String> {
+    compare_layouts(desktop_id, &LayoutSource::Saved, &LayoutSource::Live)
+}
+
+#[tauri::command]
+pub async fn compare_icon_layouts(
+    desktop_id: i32,
+    from: LayoutSource,
+    to: LayoutSource,
+) -> Result<LayoutDiff, String> {
+    compare_layouts(desktop_id, &from, &to)
+}
+
+#[tauri::command]
+pub async fn get_icon_policy(desktop_id: i32) -> Result<DesktopIconPolicy, String> {
+    Ok(policy::load_policy(desktop_id))
+}
+
+#[tauri::command]
+pub async fn set_icon_policy(desktop_id: i32, policy: DesktopIconPolicy) -> Result<(), String> {
+    policy::save_policy(desktop_id, policy)
+}
+
+/// Раскладка по зонам без изменений на экране и на диске
+#[tauri::command]
+pub async fn preview_icon_zones(desktop_id: i32) -> Result<ZonePreview, String> {
+    compute_zones(desktop_id)
+}
+
+/// Раскладывает иконки по зонам; прежняя раскладка уходит в историю
+#[tauri::command]
+pub async fn apply_icon_zones(desktop_id: i32) -> Result<ZonePreview, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let preview = compute_zones(desktop_id)?;
+
+    apply_computed_layout(desktop_id, &preview.layout)?;
+    Ok(preview)
+}
+
+/// Сетка, в которую встанут иконки, — без изменений на экране и на диске
+#[tauri::command]
+pub async fn preview_organize_icons(
+    desktop_id: i32,
+    options: OrganizeOptions,
+) -> Result<OrganizePreview, String> {
+    compute_organized(desktop_id, &options)
+}
+
+/// Упорядочивает иконки; откатывается через `undo_layout_change`
+#[tauri::command]
+pub async fn apply_organize_icons(
+    desktop_id: i32,
+    options: OrganizeOptions,
+) -> Result<OrganizePreview, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let preview = compute_organized(desktop_id, &options)?;
+
+    apply_computed_layout(desktop_id, &preview.layout)?;
+    Ok(preview)
+}
+
+#[tauri::command]
+pub async fn get_icon_autosave_settings() -> Result<IconAutosaveSettings, String> {
+    Ok(load_config().icon_autosave)
+}
+
+#[tauri::command]
+pub async fn set_icon_autosave_settings(settings: IconAutosaveSettings) -> Result<(), String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    config.icon_autosave = settings;
+    save_config(&config)
+}
+
 #[tauri::command]
 pub async fn disable_desktop_auto_arrange() -> Result<(), String> {
     #[cfg(windows)]
@@ -69,3 +249,136 @@ pub async fn disable_desktop_auto_arrange() -> Result<(), String> {
     #[cfg(not(windows))]
     Err("Только для Windows".to_string())
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Откатывает последнее изменение раскладки стола (по умолчанию — активного).
+///
+/// Если иконки на экране уже разъехались, а автосохранение их ещё не
+/// записало, достаточно вернуть сохранённую раскладку. Иначе достаём
+/// предыдущую версию из истории. `false` — откатывать нечего
+pub fn undo_last_layout_change(desktop_id: Option<i32>) -> Result<bool, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let active_id = load_config().active_desktop_id;
+    let desktop_id = desktop_id.unwrap_or(active_id);
+
+    if desktop_id <= 0 {
+        return Ok(false);
+    }
+
+    let path = get_desktop_folder_path(desktop_id);
+
+    if desktop_id == active_id {
+        let saved = load_icon_positions(&path);
+        let live = get_current_icon_positions()?;
+
+        if !saved.icons.is_empty() && !saved.same_positions(&live) {
+            restore_icon_positions(&saved, &path)?;
+            return Ok(true);
+        }
+    }
+
+    match history::pop_version(&path)? {
+        Some(layout) => {
+            apply_saved_layout(desktop_id, &layout, false)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn compare_layouts(desktop_id: i32, from: &LayoutSource, to: &LayoutSource) -> Result<LayoutDiff, String> {
+    let path = get_desktop_folder_path(desktop_id);
+    let from = resolve_layout(desktop_id, from)?;
+    let to = resolve_layout(desktop_id, to)?;
+
+    let mut result = diff::diff_layouts(&from, &to);
+    diff::check_files(&mut result, &from, &path);
+    Ok(result)
+}
+
+fn resolve_layout(desktop_id: i32, source: &LayoutSource) -> Result<DesktopIconsLayout, String> {
+    let path = get_desktop_folder_path(desktop_id);
+
+    match source {
+        LayoutSource::Saved => Ok(load_icon_positions(&path)),
+        LayoutSource::Live => {
+            // Живые позиции есть только у стола, который сейчас на экране
+            if load_config().active_desktop_id != desktop_id {
+                return Err("Рабочий стол не активен".to_string());
+            }
+            get_current_icon_positions()
+        }
+        LayoutSource::Version { id } => {
+            history::get_version(&path, *id).ok_or_else(|| "Версия раскладки не найдена".to_string())
+        }
+    }
+}
+
+fn compute_zones(desktop_id: i32) -> Result<ZonePreview, String> {
+    let zones = policy::load_policy(desktop_id).zones;
+    if zones.is_empty() {
+        return Err("Для стола не задано ни одной зоны".to_string());
+    }
+
+    let display = current_display().ok_or("Не удалось определить параметры дисплея")?;
+    let path = get_desktop_folder_path(desktop_id);
+    let base = current_or_saved_layout(desktop_id)?;
+    let items = zones::collect_items(&base, &path);
+
+    Ok(zones::compute_zone_layout(&zones, &items, &base, &display, &current_grid(&display)))
+}
+
+fn compute_organized(desktop_id: i32, options: &OrganizeOptions) -> Result<OrganizePreview, String> {
+    let display = current_display().ok_or("Не удалось определить параметры дисплея")?;
+    let path = get_desktop_folder_path(desktop_id);
+    let base = current_or_saved_layout(desktop_id)?;
+    let items = organize::collect_items(&base, &path);
+
+    Ok(organize::organize(&items, options, &base, &display, &current_grid(&display)))
+}
+
+/// Применяет рассчитанную раскладку. Живые позиции активного стола сначала
+/// сохраняются — тогда в историю попадёт то, что пользователь видел на экране
+fn apply_computed_layout(desktop_id: i32, layout: &DesktopIconsLayout) -> Result<(), String> {
+    if load_config().active_desktop_id == desktop_id {
+        let live = get_current_icon_positions()?;
+        if !live.icons.is_empty() {
+            save_icon_positions(&get_desktop_folder_path(desktop_id), &live)?;
+        }
+    }
+
+    apply_saved_layout(desktop_id, layout, true)
+}
+
+/// У активного стола правда — на экране, у остальных — в сохранённой раскладке.
+/// Сохранённая подгоняется под текущий дисплей
+fn current_or_saved_layout(desktop_id: i32) -> Result<DesktopIconsLayout, String> {
+    if load_config().active_desktop_id == desktop_id {
+        return get_current_icon_positions();
+    }
+
+    let saved = load_icon_positions(&get_desktop_folder_path(desktop_id));
+    Ok(match current_display() {
+        Some(display) => saved.fitted_to(&display),
+        None => saved,
+    })
+}
+
+/// Записывает раскладку и, если стол активен, сразу расставляет иконки
+fn apply_saved_layout(desktop_id: i32, layout: &DesktopIconsLayout, keep_history: bool) -> Result<(), String> {
+    let path = get_desktop_folder_path(desktop_id);
+    storage::save_layout(&path, layout, keep_history)?;
+
+    if load_config().active_desktop_id == desktop_id {
+        restore_icon_positions(layout, &path)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! Разовое упорядочивание иконок («навести порядок») с предпросмотром
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::diff::desktop_files;
+use super::display::DisplayGeometry;
+use super::grid::{Corner, Grid};
+use super::layout::{DesktopIconsLayout, IconPosition};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// Папки, затем файлы по расширению
+    #[default]
+    Type,
+    Name,
+    /// По времени изменения
+    Date,
+    Size,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct OrganizeOptions {
+    pub sort_by: SortKey,
+    pub descending: bool,
+    /// Угол, от которого заполняется сетка
+    pub corner: Corner,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct OrganizePreview {
+    /// Итоговая раскладка: упорядоченные иконки на новых местах, остальные —
+    /// на прежних
+    pub layout: DesktopIconsLayout,
+    /// Иконки, которым не хватило места в рабочей области; их прежние
+    /// позиции сохраняются
+    pub overflow: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrganizeItem {
+    pub name: String,
+    pub is_dir: bool,
+    /// Системные элементы вроде Корзины — не файлы
+    pub is_virtual: bool,
+    pub modified: u64,
+    pub size: u64,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Сведения об иконках стола: всё содержимое папки (в том числе файлы,
+/// которых ещё нет в раскладке) и системные элементы раскладки вроде Корзины
+pub fn collect_items(layout: &DesktopIconsLayout, desktop_path: &Path) -> Vec<OrganizeItem> {
+    let mut names: Vec<String> = desktop_files(desktop_path).into_iter().collect();
+    names.extend(layout.icons.keys().filter(|name| name.starts_with("::")).cloned());
+
+    names
+        .into_iter()
+        .map(|name| {
+            let metadata = fs::metadata(desktop_path.join(&name)).ok();
+
+            OrganizeItem {
+                is_virtual: name.starts_with("::"),
+                name,
+                is_dir: metadata.as_ref().is_some_and(|m| m.is_dir()),
+                modified: metadata
+                    .as_ref()
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+/// Целевая раскладка: иконки по порядку сортировки, столбцами от угла, в обход
+/// ячеек, занятых остальными иконками `base`. Не поместившиеся в рабочую область
+/// иконки остаются на местах из `base`
+pub fn organize(
+    items: &[OrganizeItem],
+    options: &OrganizeOptions,
+    base: &DesktopIconsLayout,
+    display: &DisplayGeometry,
+    grid: &Grid,
+) -> OrganizePreview {
+    let mut items: Vec<&OrganizeItem> = items.iter().collect();
+    items.sort_by(|a, b| {
+        // Системные элементы и папки всегда впереди, направление на них не влияет
+        let group = |i: &OrganizeItem| (!i.is_virtual, !i.is_dir);
+        group(a).cmp(&group(b)).then_with(|| {
+            let order = compare(a, b, options.sort_by);
+            if options.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        })
+    });
+
+    let mut preview = OrganizePreview {
+        layout: base.clone(),
+        ..Default::default()
+    };
+    preview.layout.display = Some(display.clone());
+
+    // Иконки, которые не упорядочиваем (например, с общего стола), стоят где стояли
+    let mut occupied: Vec<(i32, i32)> = base
+        .icons
+        .iter()
+        .filter(|(name, _)| !items.iter().any(|i| &i.name == *name))
+        .map(|(_, p)| (p.x, p.y))
+        .collect();
+
+    let area = display.client_work_area();
+
+    for item in items {
+        match grid.take_free_cell(area, options.corner, &mut occupied) {
+            Some((x, y)) => {
+                preview.layout.icons.insert(item.name.clone(), IconPosition { x, y });
+            }
+            None => preview.overflow.push(item.name.clone()),
+        }
+    }
+
+    preview
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn compare(a: &OrganizeItem, b: &OrganizeItem, key: SortKey) -> Ordering {
+    let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+
+    match key {
+        SortKey::Type => extension(&a.name).cmp(&extension(&b.name)).then_with(by_name),
+        SortKey::Name => by_name(),
+        SortKey::Date => a.modified.cmp(&b.modified).then_with(by_name),
+        SortKey::Size => a.size.cmp(&b.size).then_with(by_name),
+    }
+}
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::desktop::icons::display::LayoutRect;
+
+    fn display(width: i32, height: i32) -> DisplayGeometry {
+        let rect = LayoutRect {
+            left: 0,
+            top: 0,
+            right: width,
+            bottom: height,
+        };
+
+        DisplayGeometry {
+            bounds: rect,
+            work_area: rect,
+            dpi: 96,
+            monitors: vec![rect],
+            work_areas: vec![rect],
+        }
+    }
+
+    fn grid() -> Grid {
+        Grid {
+            cell_width: 100,
+            cell_height: 100,
+        }
+    }
+
+    fn item(name: &str) -> OrganizeItem {
+        OrganizeItem {
+            name: name.to_string(),
+            is_dir: false,
+            is_virtual: false,
+            modified: 0,
+            size: 0,
+        }
+    }
+
+    fn base(icons: &[(&str, i32, i32)]) -> DesktopIconsLayout {
+        DesktopIconsLayout {
+            icons: icons
+                .iter()
+                .map(|&(name, x, y)| (name.to_string(), IconPosition { x, y }))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn skips_cell_taken_by_foreign_icon() {
+        let base = base(&[("Public.lnk", 0, 0), ("a.txt", 500, 500)]);
+        let items = [item("a.txt"), item("b.txt")];
+
+        let preview = organize(&items, &OrganizeOptions::default(), &base, &display(1000, 300), &grid());
+
+        assert_eq!(preview.layout.icons["Public.lnk"], IconPosition { x: 0, y: 0 });
+        assert_eq!(preview.layout.icons["a.txt"], IconPosition { x: 0, y: 100 });
+        assert_eq!(preview.layout.icons["b.txt"], IconPosition { x: 0, y: 200 });
+        assert!(preview.overflow.is_empty());
+    }
+
+    #[test]
+    fn overflow_keeps_previous_position() {
+        let base = base(&[("Public.lnk", 0, 0), ("b.txt", 700, 700)]);
+        let items = [item("a.txt"), item("b.txt")];
+
+        let preview = organize(&items, &OrganizeOptions::default(), &base, &display(100, 200), &grid());
+
+        assert_eq!(preview.layout.icons["a.txt"], IconPosition { x: 0, y: 100 });
+        assert_eq!(preview.layout.icons["b.txt"], IconPosition { x: 700, y: 700 });
+        assert_eq!(preview.overflow, vec!["b.txt".to_string()]);
+    }
+
+    #[test]
+    fn folders_come_first() {
+        let mut folder = item("Zeta");
+        folder.is_dir = true;
+        let items = [item("a.txt"), folder];
+
+        let preview = organize(&items, &OrganizeOptions::default(), &base(&[]), &display(100, 300), &grid());
+
+        assert_eq!(preview.layout.icons["Zeta"], IconPosition { x: 0, y: 0 });
+        assert_eq!(preview.layout.icons["a.txt"], IconPosition { x: 0, y: 100 });
+    }
+}
@@ -0,0 +1,134 @@
+//! Куда ставить новые иконки стола: входящая зона и правила по маске имени
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::display::{DisplayGeometry, LayoutRect};
+use super::grid::{Corner, Grid};
+use super::layout::IconPosition;
+use super::zones::{zone_for, IconZone, ZoneItem};
+use crate::automation::pattern::wildcard_match;
+
+const POLICY_FILE: &str = "C:\\Kimi\\icon_policy.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DesktopIconPolicy {
+    /// Область для новых иконок, в координатах позиций иконок
+    pub incoming_zone: Option<LayoutRect>,
+    /// Проверяются по порядку, побеждает первое подходящее
+    pub extension_rules: Vec<ExtensionRule>,
+    /// Зоны главнее правил по маске: новая иконка сразу уходит в свою зону
+    pub zones: Vec<IconZone>,
+    /// Привязывать восстановленные иконки к сетке Explorer
+    pub snap_to_grid: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtensionRule {
+    /// Маска имени файла, `*` и `?`, например `*.png`
+    pub pattern: String,
+    pub corner: Corner,
+}
+
+impl Default for DesktopIconPolicy {
+    fn default() -> Self {
+        Self {
+            incoming_zone: None,
+            extension_rules: Vec::new(),
+            zones: Vec::new(),
+            snap_to_grid: true,
+        }
+    }
+}
+
+impl DesktopIconPolicy {
+    /// Нет правил размещения новых иконок
+    pub fn has_no_rules(&self) -> bool {
+        self.incoming_zone.is_none() && self.extension_rules.is_empty() && self.zones.is_empty()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Settings persistence
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn load_policy(desktop_id: i32) -> DesktopIconPolicy {
+    load_all().remove(&desktop_id).unwrap_or_default()
+}
+
+pub fn save_policy(desktop_id: i32, policy: DesktopIconPolicy) -> Result<(), String> {
+    let mut all = load_all();
+
+    if policy.has_no_rules() && policy.snap_to_grid {
+        all.remove(&desktop_id);
+    } else {
+        all.insert(desktop_id, policy);
+    }
+
+    let path = PathBuf::from(POLICY_FILE);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    let content = serde_json::to_string_pretty(&all).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn load_all() -> HashMap<i32, DesktopIconPolicy> {
+    fs::read_to_string(POLICY_FILE)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Placement
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Позиции для новых иконок. `occupied` — уже занятые точки, найденные
+/// ячейки добавляются туда же. Иконки, для которых политика ничего не
+/// говорит, остаются там, куда их поставил Explorer
+pub fn place_new_icons(
+    policy: &DesktopIconPolicy,
+    new_names: &[String],
+    occupied: &mut Vec<(i32, i32)>,
+    display: &DisplayGeometry,
+    grid: &Grid,
+) -> Vec<(String, IconPosition)> {
+    let work_area = display.client_work_area();
+
+    let mut names = new_names.to_vec();
+    names.sort();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            // Новый файл только что появился — ему ноль дней
+            let item = ZoneItem {
+                name: name.clone(),
+                age_days: Some(0),
+            };
+
+            if let Some(zone) = zone_for(&policy.zones, &item) {
+                let (x, y) = grid.take_free_cell(zone.rect, zone.corner, occupied)?;
+                return Some((name, IconPosition { x, y }));
+            }
+
+            let rule = policy
+                .extension_rules
+                .iter()
+                .find(|rule| wildcard_match(&rule.pattern, &name));
+
+            let cell = match (rule, policy.incoming_zone) {
+                (Some(rule), _) => grid.take_free_cell(work_area, rule.corner, occupied),
+                (None, Some(zone)) => grid.take_free_cell(zone, Corner::TopLeft, occupied),
+                (None, None) => None,
+            }?;
+
+            Some((name, IconPosition { x: cell.0, y: cell.1 }))
+        })
+        .collect()
+}
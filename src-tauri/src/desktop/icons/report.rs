@@ -0,0 +1,70 @@
+//! Отчёт о восстановлении раскладки иконок
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+static LAST_REPORT: Mutex<Option<RestoreReport>> = Mutex::new(None);
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RestoreReport {
+    pub timestamp: u64,
+    /// Стол Kimi, если папка — одна из папок Kimi
+    pub desktop_id: Option<i32>,
+    pub placed: Vec<String>,
+    /// Появились не сразу и встали на место при повторной попытке
+    pub placed_on_retry: Vec<String>,
+    /// Explorer так и не показал иконку с этим именем
+    pub not_found: Vec<String>,
+    pub failed: Vec<IconFailure>,
+    /// Иконки, которых не было в раскладке, расставленные по политике стола
+    pub placed_by_policy: Vec<String>,
+    /// Сколько ждали загрузки иконок и повторных попыток, мс
+    pub waited_ms: u64,
+    /// Explorer не загрузил иконки за отведённое время
+    pub wait_timed_out: bool,
+    /// Восстановление прервалось (например, окно рабочего стола недоступно)
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IconFailure {
+    pub name: String,
+    pub error: String,
+}
+
+impl RestoreReport {
+    pub fn new(desktop_id: Option<i32>) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            desktop_id,
+            ..Default::default()
+        }
+    }
+}
+
+/// Отчёты будут отправляться в интерфейс событием `icon-restore-report`
+pub fn init_restore_reports(app_handle: AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+/// Запоминает отчёт последнего восстановления и отправляет его в интерфейс —
+/// так отчёт доходит и при переключении стола, где его никто не возвращает
+pub fn record_report(report: &RestoreReport) {
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report.clone());
+    }
+
+    if let Some(app_handle) = APP_HANDLE.get() {
+        let _ = app_handle.emit("icon-restore-report", report.clone());
+    }
+}
+
+pub fn last_report() -> Option<RestoreReport> {
+    LAST_REPORT.lock().ok().and_then(|last| last.clone())
+}
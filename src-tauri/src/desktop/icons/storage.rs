@@ -1,35 +1,221 @@
 //! Сохранение и загрузка позиций иконок
 
+use super::display::current_display;
+use super::history::{all_versions, push_version, LayoutVersion};
+use super::identity::collect_identities;
 use super::layout::DesktopIconsLayout;
+use crate::config::{desktop_id_for_folder, get_desktop_folder_path, load_config, CONFIG_LOCK, KIMI_DIR};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-const COORDS_FILE: &str = "coords.kimi";
+const LAYOUTS_DIR: &str = "layouts";
+const LEGACY_COORDS_FILE: &str = "coords.kimi";
+const LEGACY_HISTORY_FILE: &str = "coords.history.kimi";
+const EXPORT_VERSION: u32 = 1;
 
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct StoredLayouts {
+    #[serde(flatten)]
+    latest: DesktopIconsLayout,
+    /// Отпечаток дисплея → раскладка
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    by_display: HashMap<String, DesktopIconsLayout>,
+}
+
+impl StoredLayouts {
+    /// Последняя сохранённая раскладка
+    pub fn into_latest(self) -> DesktopIconsLayout {
+        self.latest
+    }
+}
+
+/// Переносимая копия раскладок стола
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LayoutExport {
+    pub kimi_layout_export: u32,
+    pub desktop_name: String,
+    pub layouts: StoredLayouts,
+    #[serde(default)]
+    pub history: Vec<LayoutVersion>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DisplayLayoutSummary {
+    pub fingerprint: String,
+    pub icon_count: usize,
+    pub is_current: bool,
+}
+
+/// Раскладка для текущей конфигурации мониторов, а если на ней стол ещё
+/// не сохранялся — последняя сохранённая
 pub fn load_icon_positions(desktop_path: &Path) -> DesktopIconsLayout {
-    let coords_file = desktop_path.join(COORDS_FILE);
-    
-    if !coords_file.exists() {
-        return DesktopIconsLayout::default();
+    let fingerprint = current_display().map(|d| d.fingerprint());
+    let mut stored = read_stored(desktop_path);
+
+    fingerprint
+        .and_then(|f| stored.by_display.remove(&f))
+        .unwrap_or(stored.latest)
+}
+
+pub fn save_icon_positions(desktop_path: &Path, layout: &DesktopIconsLayout) -> Result<(), String> {
+    save_layout(desktop_path, layout, true)
+}
+
+/// `keep_history: false` — для отката и автосохранения: вытесненная раскладка в историю не попадает
+pub fn save_layout(desktop_path: &Path, layout: &DesktopIconsLayout, keep_history: bool) -> Result<(), String> {
+    let mut stored = read_stored(desktop_path);
+
+    if keep_history && !stored.latest.icons.is_empty() && !stored.latest.same_positions(layout) {
+        push_version(desktop_path, stored.latest.clone())?;
     }
-    
-    fs::read_to_string(&coords_file)
+
+    // Запоминаем, какие это файлы, чтобы пережить их переименование
+    let mut layout = layout.clone();
+    layout.identities = collect_identities(&layout, desktop_path);
+
+    if let Some(display) = &layout.display {
+        stored.by_display.insert(display.fingerprint(), layout.clone());
+    }
+    stored.latest = layout;
+
+    write_stored(desktop_path, &stored)
+}
+
+/// Конфигурации мониторов, для которых у стола есть своя раскладка
+pub fn list_display_layouts(desktop_path: &Path) -> Vec<DisplayLayoutSummary> {
+    let current = current_display().map(|d| d.fingerprint());
+    let stored = read_stored(desktop_path);
+
+    let mut summaries: Vec<DisplayLayoutSummary> = stored
+        .by_display
+        .iter()
+        .map(|(fingerprint, layout)| DisplayLayoutSummary {
+            fingerprint: fingerprint.clone(),
+            icon_count: layout.icons.len(),
+            is_current: current.as_deref() == Some(fingerprint.as_str()),
+        })
+        .collect();
+
+    summaries.sort_by(|a, b| a.fingerprint.cmp(&b.fingerprint));
+    summaries
+}
+
+pub fn remove_display_layout(desktop_path: &Path, fingerprint: &str) -> Result<(), String> {
+    let mut stored = read_stored(desktop_path);
+
+    if stored.by_display.remove(fingerprint).is_none() {
+        return Err("Раскладка для этого дисплея не найдена".to_string());
+    }
+
+    write_stored(desktop_path, &stored)
+}
+
+/// Выгружает раскладки и историю стола в файл
+pub fn export_layouts(desktop_id: i32, desktop_name: &str, target: &Path) -> Result<(), String> {
+    let desktop_path = get_desktop_folder_path(desktop_id);
+    let export = LayoutExport {
+        kimi_layout_export: EXPORT_VERSION,
+        desktop_name: desktop_name.to_string(),
+        layouts: read_stored(&desktop_path),
+        history: all_versions(&desktop_path),
+    };
+
+    let content = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    fs::write(target, content).map_err(|e| format!("Не удалось экспортировать раскладки: {}", e))
+}
+
+/// Файл раскладок стола в хранилище
+fn layout_file(desktop_path: &Path) -> PathBuf {
+    store_file(desktop_path, "json", LEGACY_COORDS_FILE)
+}
+
+/// Файл истории раскладок стола в хранилище
+pub fn history_file(desktop_path: &Path) -> PathBuf {
+    store_file(desktop_path, "history.json", LEGACY_HISTORY_FILE)
+}
+
+/// Переносит coords.kimi всех столов в хранилище — один раз, при запуске.
+/// Возвращает число перенесённых файлов
+pub fn migrate_legacy_layouts() -> Result<usize, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut migrated = 0;
+
+    for desktop in load_config().desktops {
+        let path = get_desktop_folder_path(desktop.id);
+
+        for (legacy_name, file) in [
+            (LEGACY_COORDS_FILE, layout_file(&path)),
+            (LEGACY_HISTORY_FILE, history_file(&path)),
+        ] {
+            // Ошибка с одним файлом не мешает перенести остальные
+            match migrate_file(&path.join(legacy_name), &file) {
+                Ok(true) => migrated += 1,
+                Ok(false) => {}
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+    }
+
+    Ok(migrated)
+}
+
+fn read_stored(desktop_path: &Path) -> StoredLayouts {
+    fs::read_to_string(layout_file(desktop_path))
         .ok()
         .and_then(|content| serde_json::from_str(&content).ok())
         .unwrap_or_default()
 }
 
-pub fn save_icon_positions(desktop_path: &Path, layout: &DesktopIconsLayout) -> Result<(), String> {
-    let coords_file = desktop_path.join(COORDS_FILE);
-    let content = serde_json::to_string_pretty(layout).map_err(|e| e.to_string())?;
-    
-    fs::write(&coords_file, content)
-        .map_err(|e| format!("Не удалось сохранить coords.kimi: {}", e))?;
-    
-    hide_file(&coords_file);
+fn write_stored(desktop_path: &Path, stored: &StoredLayouts) -> Result<(), String> {
+    let file = layout_file(desktop_path);
+    let content = serde_json::to_string_pretty(stored).map_err(|e| e.to_string())?;
+
+    write_store_file(&file, &content).map_err(|e| format!("Не удалось сохранить раскладку: {}", e))
+}
+
+/// Пишет файл хранилища; файлы в папке стола (если стол не из Kimi) скрываются
+pub fn write_store_file(file: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(file, content)?;
+
+    if !file.starts_with(store_dir()) {
+        hide_file(file);
+    }
     Ok(())
 }
 
+fn store_dir() -> PathBuf {
+    PathBuf::from(KIMI_DIR).join(LAYOUTS_DIR)
+}
+
+/// Путь в хранилище по id стола. Для папок вне Kimi остаётся старое место
+fn store_file(desktop_path: &Path, suffix: &str, legacy_name: &str) -> PathBuf {
+    match desktop_id_for_folder(desktop_path) {
+        Some(id) => store_dir().join(format!("Desktop{}.{}", id, suffix)),
+        None => desktop_path.join(legacy_name),
+    }
+}
+
+/// Переносит старый файл из папки стола, если в хранилище его ещё нет.
+/// Старый файл удаляется только после успешной записи
+fn migrate_file(legacy: &Path, file: &Path) -> Result<bool, String> {
+    if legacy == file || file.exists() || !legacy.exists() {
+        return Ok(false);
+    }
+
+    fs::read_to_string(legacy)
+        .and_then(|content| write_store_file(file, &content))
+        .map_err(|e| format!("Не удалось перенести {}: {}", legacy.display(), e))?;
+
+    fs::remove_file(legacy).map_err(|e| format!("Не удалось удалить {}: {}", legacy.display(), e))?;
+    Ok(true)
+}
+
 #[cfg(windows)]
 fn hide_file(path: &Path) {
     use std::ffi::OsStr;
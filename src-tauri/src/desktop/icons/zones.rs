@@ -0,0 +1,175 @@
+//! Зоны иконок («заборы»): именованные прямоугольники стола со своими условиями
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::display::{DisplayGeometry, LayoutRect};
+use super::grid::{Corner, Grid};
+use super::layout::{DesktopIconsLayout, IconPosition};
+use crate::automation::pattern::wildcard_match;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IconZone {
+    pub name: String,
+    pub rect: LayoutRect,
+    /// От какого угла зона заполняется
+    #[serde(default)]
+    pub corner: Corner,
+    #[serde(default)]
+    pub rules: ZoneRules,
+}
+
+/// Расширения и маски объединяются через «или», ограничения по возрасту —
+/// через «и». Пустые правила не подходят ни одной иконке
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ZoneRules {
+    /// Без точки: `pdf`, `docx`
+    pub extensions: Vec<String>,
+    pub name_patterns: Vec<String>,
+    pub min_age_days: Option<u64>,
+    pub max_age_days: Option<u64>,
+}
+
+/// Что известно об иконке для проверки правил
+#[derive(Debug, Clone)]
+pub struct ZoneItem {
+    pub name: String,
+    /// Дней с последнего изменения файла; `None` — не файл или не удалось узнать
+    pub age_days: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ZonePreview {
+    /// Итоговая раскладка: иконки зон на новых местах, остальные — на прежних
+    pub layout: DesktopIconsLayout,
+    pub placements: Vec<ZonePlacement>,
+    /// Иконки, которым не хватило места в своей зоне
+    pub overflow: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ZonePlacement {
+    pub name: String,
+    pub zone: String,
+    pub position: IconPosition,
+}
+
+impl ZoneRules {
+    pub fn matches(&self, item: &ZoneItem) -> bool {
+        let has_name_rules = !self.extensions.is_empty() || !self.name_patterns.is_empty();
+        let has_age_rules = self.min_age_days.is_some() || self.max_age_days.is_some();
+
+        if !has_name_rules && !has_age_rules {
+            return false;
+        }
+
+        let extension = Path::new(&item.name)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+
+        let name_ok = !has_name_rules
+            || self.extensions.iter().any(|e| {
+                extension.as_deref() == Some(e.trim_start_matches('.').to_lowercase().as_str())
+            })
+            || self
+                .name_patterns
+                .iter()
+                .any(|p| wildcard_match(p, &item.name));
+
+        let age_ok = match item.age_days {
+            Some(age) => {
+                self.min_age_days.is_none_or(|min| age >= min)
+                    && self.max_age_days.is_none_or(|max| age <= max)
+            }
+            None => !has_age_rules,
+        };
+
+        name_ok && age_ok
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Первая зона, правилам которой подходит иконка
+pub fn zone_for<'a>(zones: &'a [IconZone], item: &ZoneItem) -> Option<&'a IconZone> {
+    zones.iter().find(|zone| zone.rules.matches(item))
+}
+
+/// Раскладывает подходящие иконки `base` по зонам
+pub fn compute_zone_layout(
+    zones: &[IconZone],
+    items: &[ZoneItem],
+    base: &DesktopIconsLayout,
+    display: &DisplayGeometry,
+    grid: &Grid,
+) -> ZonePreview {
+    let mut items: Vec<&ZoneItem> = items.iter().collect();
+    items.sort_by_key(|item| item.name.to_lowercase());
+
+    let mut preview = ZonePreview {
+        layout: base.clone(),
+        ..Default::default()
+    };
+    preview.layout.display = Some(display.clone());
+
+    // Иконки вне зон стоят где стояли — их ячейки заняты
+    let mut occupied: Vec<(i32, i32)> = base
+        .icons
+        .iter()
+        .filter(|(name, _)| {
+            !items
+                .iter()
+                .any(|i| &i.name == *name && zone_for(zones, i).is_some())
+        })
+        .map(|(_, p)| (p.x, p.y))
+        .collect();
+
+    for item in items {
+        let Some(zone) = zone_for(zones, item) else {
+            continue;
+        };
+
+        match grid.take_free_cell(zone.rect, zone.corner, &mut occupied) {
+            Some((x, y)) => {
+                let position = IconPosition { x, y };
+                preview
+                    .layout
+                    .icons
+                    .insert(item.name.clone(), position.clone());
+                preview.placements.push(ZonePlacement {
+                    name: item.name.clone(),
+                    zone: zone.name.clone(),
+                    position,
+                });
+            }
+            None => preview.overflow.push(item.name.clone()),
+        }
+    }
+
+    preview
+}
+
+/// Сведения об иконках раскладки для проверки правил
+pub fn collect_items(layout: &DesktopIconsLayout, desktop_path: &Path) -> Vec<ZoneItem> {
+    let now = SystemTime::now();
+
+    layout
+        .icons
+        .keys()
+        .map(|name| ZoneItem {
+            name: name.clone(),
+            age_days: fs::metadata(desktop_path.join(name))
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .map(|age| age.as_secs() / SECS_PER_DAY),
+        })
+        .collect()
+}
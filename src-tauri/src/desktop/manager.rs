@@ -4,12 +4,13 @@ use std::fs;
 
 use crate::config::{
     count_files_in_dir, ensure_kimi_dir, get_desktop_folder_path, load_config, save_config,
-    Desktop, DesktopConfig, CONFIG_LOCK, KIMI_DIR, MAX_DESKTOPS,
+    Desktop, DesktopConfig, KimiConfig, CONFIG_LOCK, KIMI_DIR, MAX_DESKTOPS,
 };
 use super::icons::{
     get_current_icon_positions, load_icon_positions, restore_icon_positions, save_icon_positions,
 };
 use super::shell::set_desktop_path;
+use crate::virtual_desktop::names::push_name_to_windows;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Tauri Commands
@@ -47,28 +48,13 @@ pub async fn create_desktop() -> Result<Desktop, String> {
     
     let mut config = load_config();
 
-    if config.desktops.len() >= MAX_DESKTOPS {
-        return Err(format!("Максимум {} рабочих столов", MAX_DESKTOPS));
-    }
-
-    let new_id = config.desktops.iter().map(|d| d.id).max().unwrap_or(0) + 1;
-    let desktop_path = get_desktop_folder_path(new_id);
-
-    fs::create_dir_all(&desktop_path)
-        .map_err(|e| format!("Не удалось создать папку: {}", e))?;
-
-    let new_config = DesktopConfig {
-        id: new_id,
-        name: format!("Рабочий стол {}", new_id),
-    };
-
-    config.desktops.push(new_config.clone());
+    let new_config = add_desktop(&mut config, None)?;
     save_config(&config)?;
 
     Ok(Desktop {
-        id: new_id,
+        id: new_config.id,
         name: new_config.name,
-        path: desktop_path.to_string_lossy().to_string(),
+        path: get_desktop_folder_path(new_config.id).to_string_lossy().to_string(),
         is_active: false,
         file_count: 0,
     })
@@ -104,20 +90,41 @@ pub async fn switch_desktop(id: i32) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn delete_desktop(id: i32) -> Result<(), String> {
+pub async fn rename_desktop(id: i32, name: String) -> Result<(), String> {
     let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
     let mut config = load_config();
 
-    if config.desktops.len() <= 1 {
-        return Err("Нельзя удалить последний рабочий стол".to_string());
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Имя рабочего стола не может быть пустым".to_string());
     }
 
-    if config.active_desktop_id == id {
-        return Err("Нельзя удалить активный рабочий стол".to_string());
+    let desktop = config
+        .desktops
+        .iter_mut()
+        .find(|d| d.id == id)
+        .ok_or("Рабочий стол не найден")?;
+
+    desktop.name = name.clone();
+    save_config(&config)?;
+
+    if let Err(e) = push_name_to_windows(&config, id, &name) {
+        eprintln!("Не удалось переименовать виртуальный стол: {}", e);
     }
 
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_desktop(id: i32) -> Result<(), String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    check_can_delete(&config, id)?;
+
     // Удаляем только из конфига, папка остаётся
     config.desktops.retain(|d| d.id != id);
+    config.virtual_desktop_mapping.remove(&id);
     save_config(&config)?;
 
     Ok(())
@@ -146,6 +153,49 @@ pub async fn get_original_desktop_path() -> Result<String, String> {
     Ok(config.original_desktop_path)
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Добавляет стол в конфиг и создаёт его папку. Сохранение — за вызывающим
+pub fn add_desktop(config: &mut KimiConfig, name: Option<String>) -> Result<DesktopConfig, String> {
+    if config.desktops.len() >= MAX_DESKTOPS {
+        return Err(format!("Максимум {} рабочих столов", MAX_DESKTOPS));
+    }
+
+    let new_id = config.desktops.iter().map(|d| d.id).max().unwrap_or(0) + 1;
+
+    fs::create_dir_all(get_desktop_folder_path(new_id))
+        .map_err(|e| format!("Не удалось создать папку: {}", e))?;
+
+    let new_config = DesktopConfig {
+        id: new_id,
+        name: name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("Рабочий стол {}", new_id)),
+    };
+
+    config.desktops.push(new_config.clone());
+    Ok(new_config)
+}
+
+pub fn check_can_delete(config: &KimiConfig, id: i32) -> Result<(), String> {
+    if !config.desktops.iter().any(|d| d.id == id) {
+        return Err("Рабочий стол не найден".to_string());
+    }
+
+    if config.desktops.len() <= 1 {
+        return Err("Нельзя удалить последний рабочий стол".to_string());
+    }
+
+    if config.active_desktop_id == id {
+        return Err("Нельзя удалить активный рабочий стол".to_string());
+    }
+
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Startup validation
 // ─────────────────────────────────────────────────────────────────────────────
@@ -181,7 +231,7 @@ pub fn validate_and_restore_if_needed() {
 // Internal helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
-fn save_current_icons(config: &crate::config::KimiConfig) {
+fn save_current_icons(config: &KimiConfig) {
     if config.active_desktop_id <= 0 {
         return;
     }
@@ -195,7 +245,7 @@ fn save_current_icons(config: &crate::config::KimiConfig) {
     }
 }
 
-fn restore_icons_for_desktop(desktop_path: &std::path::PathBuf) {
+fn restore_icons_for_desktop(desktop_path: &std::path::Path) {
     let layout = load_icon_positions(desktop_path);
     
     if !layout.icons.is_empty() {
@@ -17,6 +17,9 @@ const SETTINGS_FILE: &str = "C:\\Kimi\\hotkeys.json";
 pub struct HotkeySettings {
     pub enabled: bool,
     pub modifier: String, // "alt", "ctrl+alt", "ctrl+shift"
+    /// Буква, которая с тем же модификатором откатывает раскладку иконок
+    #[serde(default)]
+    pub undo_layout_key: Option<String>,
 }
 
 impl Default for HotkeySettings {
@@ -24,6 +27,7 @@ impl Default for HotkeySettings {
         Self {
             enabled: true,
             modifier: "alt".to_string(),
+            undo_layout_key: None,
         }
     }
 }
@@ -106,6 +110,7 @@ pub fn start_hotkey_listener(_app_handle: AppHandle, _running: Arc<AtomicBool>)
 #[cfg(windows)]
 mod hook {
     use super::{load_hotkey_settings, HotkeySettings};
+    use crate::desktop::icons::undo_last_layout_change;
     use std::sync::{Mutex, OnceLock};
     use tauri::{AppHandle, Emitter};
     use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
@@ -159,36 +164,56 @@ mod hook {
                     return LRESULT(1);
                 }
             }
+
+            if is_keydown && is_undo_layout_hotkey(&kb) {
+                // Хук должен вернуться быстро — откат идёт в отдельном потоке
+                std::thread::spawn(undo_layout);
+                return LRESULT(1);
+            }
         }
 
         CallNextHookEx(None, code, wparam, lparam)
     }
 
     unsafe fn try_handle_hotkey(kb: &KBDLLHOOKSTRUCT) -> Option<i32> {
-        let settings = SETTINGS
-            .get()?
-            .lock()
-            .ok()?
-            .clone();
+        let settings = current_settings()?;
 
-        if !settings.enabled {
-            return None;
+        if settings.enabled && modifier_matches(&settings, kb) {
+            Some((kb.vkCode - 0x30) as i32)
+        } else {
+            None
         }
+    }
+
+    unsafe fn is_undo_layout_hotkey(kb: &KBDLLHOOKSTRUCT) -> bool {
+        let Some(settings) = current_settings() else {
+            return false;
+        };
+
+        // Цифры заняты переключением столов, поэтому только буквы
+        let key = settings
+            .undo_layout_key
+            .as_deref()
+            .and_then(|k| k.trim().chars().next())
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase() as u32);
+
+        settings.enabled && key == Some(kb.vkCode) && modifier_matches(&settings, kb)
+    }
+
+    fn current_settings() -> Option<HotkeySettings> {
+        SETTINGS.get()?.lock().ok().map(|g| g.clone())
+    }
 
+    unsafe fn modifier_matches(settings: &HotkeySettings, kb: &KBDLLHOOKSTRUCT) -> bool {
         let alt = (kb.flags.0 & 0x20) != 0 || is_key_pressed(0x12);
         let ctrl = is_key_pressed(0x11);
         let shift = is_key_pressed(0x10);
 
-        let modifier_match = match settings.modifier.as_str() {
+        match settings.modifier.as_str() {
             "ctrl+alt" => ctrl && alt && !shift,
             "ctrl+shift" => ctrl && shift && !alt,
             _ => alt && !ctrl && !shift,
-        };
-
-        if modifier_match {
-            Some((kb.vkCode - 0x30) as i32)
-        } else {
-            None
         }
     }
 
@@ -196,13 +221,24 @@ mod hook {
         (GetAsyncKeyState(vk) as u16 & 0x8000) != 0
     }
 
-    fn emit_switch_event(desktop_num: i32) {
+    fn undo_layout() {
+        match undo_last_layout_change(None) {
+            Ok(undone) => emit_event("icon-layout-undone", undone),
+            Err(e) => eprintln!("Не удалось откатить раскладку иконок: {}", e),
+        }
+    }
+
+    fn emit_event<S: serde::Serialize + Clone>(event: &str, payload: S) {
         if let Some(app_mutex) = APP_HANDLE.get() {
             if let Ok(guard) = app_mutex.lock() {
                 if let Some(ref app) = *guard {
-                    let _ = app.emit("hotkey-switch-desktop", desktop_num);
+                    let _ = app.emit(event, payload);
                 }
             }
         }
     }
+
+    fn emit_switch_event(desktop_num: i32) {
+        emit_event("hotkey-switch-desktop", desktop_num);
+    }
 }
@@ -1,5 +1,6 @@
 //! Kimi - менеджер виртуальных рабочих столов
 
+mod automation;
 mod autostart;
 mod config;
 mod desktop;
@@ -33,6 +34,15 @@ pub fn run() {
         .setup(move |app| {
             tray::setup_system_tray(app.handle())?;
             hotkeys::start_hotkey_listener(app.handle().clone(), hotkey_running_clone.clone());
+            automation::start_automation(app.handle().clone());
+            virtual_desktop::events::start_desktop_event_listener(app.handle().clone());
+            desktop::icons::init_restore_reports(app.handle().clone());
+            if let Err(e) = desktop::icons::migrate_legacy_layouts() {
+                eprintln!("Перенос раскладок иконок: {}", e);
+            }
+            desktop::icons::start_display_watcher(app.handle().clone());
+            desktop::icons::start_icon_autosave();
+            desktop::icons::start_incoming_watcher();
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -46,6 +56,7 @@ pub fn run() {
             desktop::manager::get_desktops,
             desktop::manager::create_desktop,
             desktop::manager::switch_desktop,
+            desktop::manager::rename_desktop,
             desktop::manager::delete_desktop,
             desktop::manager::restore_original_desktop,
             desktop::manager::get_original_desktop_path,
@@ -54,29 +65,66 @@ pub fn run() {
             desktop::icons::restore_desktop_icons,
             desktop::icons::get_saved_icon_positions,
             desktop::icons::force_save_current_layout,
+            desktop::icons::get_display_layouts,
+            desktop::icons::delete_display_layout,
+            desktop::icons::export_icon_layouts,
+            desktop::icons::get_last_restore_report,
+            desktop::icons::preview_icon_import,
+            desktop::icons::import_icon_layout,
+            desktop::icons::list_layout_versions,
+            desktop::icons::restore_layout_version,
+            desktop::icons::undo_layout_change,
+            desktop::icons::diff_live_layout,
+            desktop::icons::compare_icon_layouts,
+            desktop::icons::get_icon_policy,
+            desktop::icons::set_icon_policy,
+            desktop::icons::preview_icon_zones,
+            desktop::icons::apply_icon_zones,
+            desktop::icons::preview_organize_icons,
+            desktop::icons::apply_organize_icons,
+            desktop::icons::get_icon_autosave_settings,
+            desktop::icons::set_icon_autosave_settings,
             desktop::icons::disable_desktop_auto_arrange,
             // Virtual desktops
             virtual_desktop::api::get_virtual_desktop_count,
+            virtual_desktop::api::list_virtual_desktops,
+            virtual_desktop::reconcile::check_virtual_desktops,
+            virtual_desktop::reconcile::apply_reconcile_actions,
             virtual_desktop::api::get_current_virtual_desktop,
             virtual_desktop::api::switch_virtual_desktop,
-            virtual_desktop::keyboard::create_virtual_desktop,
-            virtual_desktop::keyboard::remove_current_virtual_desktop,
-            virtual_desktop::keyboard::switch_desktop_left,
-            virtual_desktop::keyboard::switch_desktop_right,
+            virtual_desktop::manage::create_virtual_desktop,
+            virtual_desktop::manage::remove_current_virtual_desktop,
+            virtual_desktop::manage::remove_virtual_desktop,
+            virtual_desktop::manage::switch_desktop_left,
+            virtual_desktop::manage::switch_desktop_right,
+            virtual_desktop::manage::get_virtual_desktop_capabilities,
             virtual_desktop::api::move_window_to_desktop,
             virtual_desktop::api::is_window_on_current_desktop,
             virtual_desktop::api::get_window_desktop_index,
             virtual_desktop::api::get_all_windows,
             virtual_desktop::api::get_windows_on_current_desktop,
+            virtual_desktop::api::pin_window,
+            virtual_desktop::api::unpin_window,
+            virtual_desktop::api::pin_app,
+            virtual_desktop::api::unpin_app,
+            virtual_desktop::api::list_pinned,
+            virtual_desktop::search::search_windows,
+            virtual_desktop::search::jump_to_window,
+            virtual_desktop::names::sync_desktop_names,
+            virtual_desktop::names::get_name_sync_settings,
+            virtual_desktop::names::set_name_sync_settings,
             // Taskbar
             taskbar::enable_taskbar_filter,
             taskbar::disable_taskbar_filter,
             taskbar::get_taskbar_filter_status,
             // Workspace
             workspace::switch_workspace,
+            workspace::delete_workspace,
             workspace::link_to_virtual_desktop,
             workspace::unlink_from_virtual_desktop,
             workspace::get_workspace_mappings,
+            workspace::get_follow_virtual_desktops,
+            workspace::set_follow_virtual_desktops,
             // Tray
             tray::show_main_window,
             tray::exit_app,
@@ -87,6 +135,13 @@ pub fn run() {
             // Autostart
             autostart::get_autostart_enabled,
             autostart::set_autostart_enabled,
+            // Automation
+            automation::settings::get_automation_settings,
+            automation::settings::set_automation_settings,
+            automation::settings::set_automation_paused,
+            automation::placement::get_placement_log,
+            automation::placement::clear_placement_log,
+            automation::placement::apply_placement_rules,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
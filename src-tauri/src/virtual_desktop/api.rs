@@ -2,12 +2,46 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::load_config;
+use crate::workspace::resolve_kimi_desktop;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WindowInfo {
     pub hwnd: isize,
     pub title: String,
     pub process_id: u32,
+    pub process_name: String,
+    pub exe_path: String,
+    pub class_name: String,
+    pub owner_hwnd: Option<isize>,
+    pub is_tool_window: bool,
+    pub is_cloaked: bool,
+    pub is_minimized: bool,
+    pub is_maximized: bool,
+    pub rect: WindowRect,
+    /// Имя устройства монитора, например `\\.\DISPLAY1`
+    pub monitor: Option<String>,
     pub desktop_index: Option<usize>,
+    pub is_pinned: bool,
+    pub is_app_pinned: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct WindowRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VirtualDesktopInfo {
+    pub index: usize,
+    /// GUID стола — по нему строятся связи со столами Kimi
+    pub id: String,
+    pub name: String,
+    pub is_current: bool,
+    pub kimi_desktop_id: Option<i32>,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -24,6 +58,26 @@ pub async fn get_current_virtual_desktop() -> Result<usize, String> {
     platform::get_current_desktop()
 }
 
+#[tauri::command]
+pub async fn list_virtual_desktops() -> Result<Vec<VirtualDesktopInfo>, String> {
+    let desktops = platform::list_desktops()?;
+    let current = platform::get_current_desktop().ok();
+    let ids: Vec<String> = desktops.iter().map(|(id, _)| id.clone()).collect();
+    let config = load_config();
+
+    Ok(desktops
+        .into_iter()
+        .enumerate()
+        .map(|(index, (id, name))| VirtualDesktopInfo {
+            index,
+            id,
+            name,
+            is_current: current == Some(index),
+            kimi_desktop_id: resolve_kimi_desktop(&config, index, &ids),
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub async fn switch_virtual_desktop(index: usize) -> Result<(), String> {
     platform::switch_to_desktop(index)
@@ -57,6 +111,79 @@ pub async fn get_windows_on_current_desktop() -> Result<Vec<WindowInfo>, String>
     Ok(all.into_iter().filter(|w| w.desktop_index == Some(current)).collect())
 }
 
+#[tauri::command]
+pub async fn pin_window(hwnd_raw: isize) -> Result<(), String> {
+    platform::set_window_pinned(hwnd_raw, true)
+}
+
+#[tauri::command]
+pub async fn unpin_window(hwnd_raw: isize) -> Result<(), String> {
+    platform::set_window_pinned(hwnd_raw, false)
+}
+
+#[tauri::command]
+pub async fn pin_app(hwnd_raw: isize) -> Result<(), String> {
+    platform::set_app_pinned(hwnd_raw, true)
+}
+
+#[tauri::command]
+pub async fn unpin_app(hwnd_raw: isize) -> Result<(), String> {
+    platform::set_app_pinned(hwnd_raw, false)
+}
+
+#[tauri::command]
+pub async fn list_pinned() -> Result<Vec<WindowInfo>, String> {
+    let all = platform::enumerate_windows()?;
+    Ok(all.into_iter().filter(|w| w.is_pinned || w.is_app_pinned).collect())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public helper
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn desktop_count() -> Result<usize, String> {
+    platform::get_desktop_count()
+}
+
+pub fn current_desktop_index() -> Result<usize, String> {
+    platform::get_current_desktop()
+}
+
+pub fn enumerate_windows() -> Result<Vec<WindowInfo>, String> {
+    platform::enumerate_windows()
+}
+
+/// GUID и имена виртуальных столов в порядке их индексов
+pub fn list_desktops() -> Result<Vec<(String, String)>, String> {
+    platform::list_desktops()
+}
+
+/// GUID виртуальных столов в порядке их индексов
+pub fn virtual_desktop_ids() -> Result<Vec<String>, String> {
+    Ok(platform::list_desktops()?.into_iter().map(|(id, _)| id).collect())
+}
+
+pub fn get_window_info(hwnd_raw: isize) -> Option<WindowInfo> {
+    platform::window_info(hwnd_raw)
+}
+
+/// Окно, которое пользователь увидел бы в Task View
+pub fn is_task_view_window(window: &WindowInfo) -> bool {
+    platform::is_task_view_window(window)
+}
+
+pub fn switch_to_desktop(index: usize) -> Result<(), String> {
+    platform::switch_to_desktop(index)
+}
+
+pub fn move_window(hwnd_raw: isize, desktop_index: usize) -> Result<(), String> {
+    platform::move_window(hwnd_raw, desktop_index)
+}
+
+pub fn pin_app_of_window(hwnd_raw: isize) -> Result<(), String> {
+    platform::set_app_pinned(hwnd_raw, true)
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Platform-specific implementation
 // ─────────────────────────────────────────────────────────────────────────────
@@ -64,9 +191,20 @@ pub async fn get_windows_on_current_desktop() -> Result<Vec<WindowInfo>, String>
 #[cfg(windows)]
 mod platform {
     use super::WindowInfo;
-    use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use super::WindowRect;
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM, RECT};
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED, DWM_CLOAKED_SHELL};
+    use windows::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
+    };
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
     use windows::Win32::UI::WindowsAndMessaging::{
-        EnumWindows, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible,
+        EnumWindows, GetClassNameW, GetWindow, GetWindowLongW, GetWindowRect, GetWindowTextLengthW,
+        GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindowVisible, IsZoomed, GW_OWNER,
+        GWL_EXSTYLE, WS_EX_APPWINDOW, WS_EX_TOOLWINDOW,
     };
 
     pub fn get_desktop_count() -> Result<usize, String> {
@@ -76,23 +214,60 @@ mod platform {
     }
 
     pub fn get_current_desktop() -> Result<usize, String> {
-        winvd::get_current_desktop().map_err(|e| format!("{:?}", e))
+        winvd::get_current_desktop()
+            .and_then(|d| d.get_index())
+            .map(|i| i as usize)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn list_desktops() -> Result<Vec<(String, String)>, String> {
+        let desktops = winvd::get_desktops().map_err(|e| format!("{:?}", e))?;
+
+        desktops
+            .iter()
+            .map(|d| {
+                let id = d.get_id().map_err(|e| format!("{:?}", e))?;
+                let name = d.get_name().map_err(|e| format!("{:?}", e))?;
+                Ok((format!("{:?}", id), name))
+            })
+            .collect()
     }
 
     pub fn switch_to_desktop(index: usize) -> Result<(), String> {
-        winvd::go_to_desktop(index).map_err(|e| format!("{:?}", e))
+        winvd::switch_desktop(index as u32).map_err(|e| format!("{:?}", e))
     }
 
     pub fn move_window(hwnd: isize, desktop: usize) -> Result<(), String> {
-        winvd::move_window_to_desktop(hwnd as u32, desktop).map_err(|e| format!("{:?}", e))
+        winvd::move_window_to_desktop(desktop as u32, &to_hwnd(hwnd)).map_err(|e| format!("{:?}", e))
     }
 
     pub fn is_on_current_desktop(hwnd: isize) -> Result<bool, String> {
-        winvd::is_window_on_current_virtual_desktop(hwnd as u32).map_err(|e| format!("{:?}", e))
+        winvd::is_window_on_current_desktop(to_hwnd(hwnd)).map_err(|e| format!("{:?}", e))
     }
 
     pub fn get_window_desktop(hwnd: isize) -> Result<usize, String> {
-        winvd::get_desktop_by_window(hwnd as u32).map_err(|e| format!("{:?}", e))
+        winvd::get_desktop_by_window(to_hwnd(hwnd))
+            .and_then(|d| d.get_index())
+            .map(|i| i as usize)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn set_window_pinned(hwnd: isize, pinned: bool) -> Result<(), String> {
+        let result = if pinned {
+            winvd::pin_window(to_hwnd(hwnd))
+        } else {
+            winvd::unpin_window(to_hwnd(hwnd))
+        };
+        result.map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn set_app_pinned(hwnd: isize, pinned: bool) -> Result<(), String> {
+        let result = if pinned {
+            winvd::pin_app(to_hwnd(hwnd))
+        } else {
+            winvd::unpin_app(to_hwnd(hwnd))
+        };
+        result.map_err(|e| format!("{:?}", e))
     }
 
     pub fn enumerate_windows() -> Result<Vec<WindowInfo>, String> {
@@ -104,24 +279,9 @@ mod platform {
             let data = &mut *(lparam.0 as *mut EnumData);
 
             if IsWindowVisible(hwnd).as_bool() {
-                let len = GetWindowTextLengthW(hwnd);
-                if len > 0 {
-                    let mut title = vec![0u16; (len + 1) as usize];
-                    GetWindowTextW(hwnd, &mut title);
-                    let title_str = String::from_utf16_lossy(&title[..len as usize]);
-
-                    if !title_str.is_empty() {
-                        let mut pid = 0u32;
-                        GetWindowThreadProcessId(hwnd, Some(&mut pid));
-
-                        let desktop_index = winvd::get_desktop_by_window(hwnd.0 as u32).ok();
-
-                        data.windows.push(WindowInfo {
-                            hwnd: hwnd.0 as isize,
-                            title: title_str,
-                            process_id: pid,
-                            desktop_index,
-                        });
+                if let Some(info) = window_info(hwnd.0 as isize) {
+                    if is_task_view_window(&info) {
+                        data.windows.push(info);
                     }
                 }
             }
@@ -136,6 +296,143 @@ mod platform {
 
         Ok(data.windows)
     }
+
+    pub fn window_info(hwnd_raw: isize) -> Option<WindowInfo> {
+        let hwnd = to_hwnd(hwnd_raw);
+
+        unsafe {
+            let len = GetWindowTextLengthW(hwnd);
+            if len <= 0 {
+                return None;
+            }
+
+            let mut title = vec![0u16; (len + 1) as usize];
+            GetWindowTextW(hwnd, &mut title);
+            let title_str = String::from_utf16_lossy(&title[..len as usize]);
+
+            if title_str.is_empty() {
+                return None;
+            }
+
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+
+            let exe_path = process_path(pid).unwrap_or_default();
+            let process_name = exe_path.rsplit('\\').next().unwrap_or_default().to_string();
+            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+
+            let mut rect = RECT::default();
+            let _ = GetWindowRect(hwnd, &mut rect);
+
+            Some(WindowInfo {
+                hwnd: hwnd_raw,
+                title: title_str,
+                process_id: pid,
+                process_name,
+                exe_path,
+                class_name: class_name(hwnd),
+                owner_hwnd: GetWindow(hwnd, GW_OWNER).ok().map(|h| h.0 as isize),
+                is_tool_window: ex_style & WS_EX_TOOLWINDOW.0 != 0,
+                is_cloaked: cloak_flags(hwnd) != 0,
+                is_minimized: IsIconic(hwnd).as_bool(),
+                is_maximized: IsZoomed(hwnd).as_bool(),
+                rect: WindowRect {
+                    left: rect.left,
+                    top: rect.top,
+                    right: rect.right,
+                    bottom: rect.bottom,
+                },
+                monitor: monitor_name(hwnd),
+                desktop_index: get_window_desktop(hwnd_raw).ok(),
+                is_pinned: winvd::is_pinned_window(hwnd).unwrap_or(false),
+                is_app_pinned: winvd::is_pinned_app(hwnd).unwrap_or(false),
+            })
+        }
+    }
+
+    /// Те же правила, что у Task View / Alt+Tab: без служебных окон оболочки,
+    /// вспомогательных окон и «призрачных» рамок UWP
+    pub fn is_task_view_window(info: &WindowInfo) -> bool {
+        if matches!(info.class_name.as_str(), "Progman" | "WorkerW" | "Shell_TrayWnd") {
+            return false;
+        }
+
+        let hwnd = to_hwnd(info.hwnd);
+        let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+        let app_window = ex_style & WS_EX_APPWINDOW.0 != 0;
+
+        if !app_window && (info.is_tool_window || info.owner_hwnd.is_some()) {
+            return false;
+        }
+
+        // Окна других виртуальных столов скрыты оболочкой — их показываем.
+        // Скрытое оболочкой на текущем столе (приостановленные рамки UWP)
+        // и всё, что скрыто самим приложением, пропускаем
+        match unsafe { cloak_flags(hwnd) } {
+            0 => true,
+            DWM_CLOAKED_SHELL => {
+                info.desktop_index.is_some() && !is_on_current_desktop(info.hwnd).unwrap_or(true)
+            }
+            _ => false,
+        }
+    }
+
+    unsafe fn cloak_flags(hwnd: HWND) -> u32 {
+        let mut cloaked = 0u32;
+        let _ = DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut _,
+            std::mem::size_of::<u32>() as u32,
+        );
+        cloaked
+    }
+
+    unsafe fn class_name(hwnd: HWND) -> String {
+        let mut buf = [0u16; 256];
+        let len = GetClassNameW(hwnd, &mut buf);
+        String::from_utf16_lossy(&buf[..len.max(0) as usize])
+    }
+
+    unsafe fn monitor_name(hwnd: HWND) -> Option<String> {
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        if monitor.is_invalid() {
+            return None;
+        }
+
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+
+        if !GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO).as_bool() {
+            return None;
+        }
+
+        let len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+        Some(String::from_utf16_lossy(&info.szDevice[..len]))
+    }
+
+    fn process_path(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+            let mut buf = [0u16; 1024];
+            let mut size = buf.len() as u32;
+            let result = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buf.as_mut_ptr()),
+                &mut size,
+            );
+            let _ = CloseHandle(process);
+            result.ok()?;
+
+            Some(String::from_utf16_lossy(&buf[..size as usize]))
+        }
+    }
+
+    fn to_hwnd(hwnd: isize) -> HWND {
+        HWND(hwnd as *mut _)
+    }
 }
 
 #[cfg(not(windows))]
@@ -150,6 +447,10 @@ mod platform {
         Err("Только для Windows".to_string())
     }
 
+    pub fn list_desktops() -> Result<Vec<(String, String)>, String> {
+        Err("Только для Windows".to_string())
+    }
+
     pub fn switch_to_desktop(_: usize) -> Result<(), String> {
         Err("Только для Windows".to_string())
     }
@@ -169,4 +470,20 @@ mod platform {
     pub fn enumerate_windows() -> Result<Vec<WindowInfo>, String> {
         Err("Только для Windows".to_string())
     }
+
+    pub fn set_window_pinned(_: isize, _: bool) -> Result<(), String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn set_app_pinned(_: isize, _: bool) -> Result<(), String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn window_info(_: isize) -> Option<WindowInfo> {
+        None
+    }
+
+    pub fn is_task_view_window(_: &WindowInfo) -> bool {
+        false
+    }
 }
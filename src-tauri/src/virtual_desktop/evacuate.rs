@@ -0,0 +1,167 @@
+//! Перенос окон с виртуального стола перед его удалением
+
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::api::{enumerate_windows, is_task_view_window, move_window, WindowInfo};
+
+/// Сколько ждём, пока окна закроются после WM_CLOSE
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+const CLOSE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EvacuationTarget {
+    /// На виртуальный стол, связанный с этим столом Kimi
+    KimiDesktop { desktop_id: i32 },
+    /// На стол слева (для первого стола — на следующий)
+    Previous,
+    /// Отправить окнам WM_CLOSE: приложение само спросит про несохранённое
+    Close,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct EvacuationReport {
+    pub removed_index: Option<usize>,
+    pub target_index: Option<usize>,
+    pub moved: Vec<EvacuatedWindow>,
+    pub closed: Vec<EvacuatedWindow>,
+    /// Окно получило WM_CLOSE, но не закрылось — скорее всего, спрашивает
+    /// про несохранённые изменения и после удаления стола окажется на соседнем
+    pub close_requested: Vec<EvacuatedWindow>,
+    pub failed: Vec<EvacuationFailure>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EvacuatedWindow {
+    pub hwnd: isize,
+    pub title: String,
+    pub process_name: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EvacuationFailure {
+    pub window: EvacuatedWindow,
+    pub error: String,
+}
+
+impl From<&WindowInfo> for EvacuatedWindow {
+    fn from(window: &WindowInfo) -> Self {
+        Self {
+            hwnd: window.hwnd,
+            title: window.title.clone(),
+            process_name: window.process_name.clone(),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Стол по умолчанию для окон удаляемого стола
+pub fn previous_index(index: usize) -> usize {
+    if index > 0 {
+        index - 1
+    } else {
+        1
+    }
+}
+
+/// Переносит (или закрывает) окна стола `index`. Сам стол не удаляется.
+/// `target` — индекс стола назначения, `None` — закрыть окна
+pub fn evacuate_windows(index: usize, target: Option<usize>) -> Result<EvacuationReport, String> {
+    let mut report = EvacuationReport {
+        removed_index: Some(index),
+        target_index: target,
+        ..Default::default()
+    };
+
+    // Закреплённые окна видны на всех столах — удаление их не затрагивает
+    let windows = enumerate_windows()?.into_iter().filter(|w| {
+        w.desktop_index == Some(index) && !w.is_pinned && !w.is_app_pinned && is_task_view_window(w)
+    });
+
+    let mut closing = Vec::new();
+
+    for window in windows {
+        let result = match target {
+            Some(target) => move_window(window.hwnd, target),
+            None => platform::close_window(window.hwnd),
+        };
+
+        match (result, target) {
+            (Ok(()), Some(_)) => report.moved.push((&window).into()),
+            (Ok(()), None) => closing.push(EvacuatedWindow::from(&window)),
+            (Err(error), _) => report.failed.push(EvacuationFailure {
+                window: (&window).into(),
+                error,
+            }),
+        }
+    }
+
+    let (closed, still_open) = wait_for_close(closing);
+    report.closed = closed;
+    report.close_requested = still_open;
+
+    Ok(report)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Ждёт закрытия окон. WM_CLOSE только ставится в очередь, так что окно
+/// считается закрытым, лишь когда его больше нет
+fn wait_for_close(
+    mut windows: Vec<EvacuatedWindow>,
+) -> (Vec<EvacuatedWindow>, Vec<EvacuatedWindow>) {
+    let started = Instant::now();
+    let mut closed = Vec::new();
+
+    while !windows.is_empty() {
+        let (gone, alive): (Vec<_>, Vec<_>) = windows
+            .into_iter()
+            .partition(|w| !platform::is_window(w.hwnd));
+        closed.extend(gone);
+        windows = alive;
+
+        if windows.is_empty() || started.elapsed() >= CLOSE_TIMEOUT {
+            break;
+        }
+        thread::sleep(CLOSE_POLL_INTERVAL);
+    }
+
+    (closed, windows)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{IsWindow, PostMessageW, WM_CLOSE};
+
+    pub fn close_window(hwnd: isize) -> Result<(), String> {
+        unsafe { PostMessageW(HWND(hwnd as *mut _), WM_CLOSE, WPARAM(0), LPARAM(0)) }
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn is_window(hwnd: isize) -> bool {
+        unsafe { IsWindow(HWND(hwnd as *mut _)).as_bool() }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn close_window(_: isize) -> Result<(), String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn is_window(_: isize) -> bool {
+        false
+    }
+}
@@ -0,0 +1,105 @@
+//! Подписка на уведомления виртуальных столов Windows
+
+use tauri::AppHandle;
+
+#[cfg(windows)]
+pub fn start_desktop_event_listener(app_handle: AppHandle) {
+    listener::start(app_handle);
+}
+
+#[cfg(not(windows))]
+pub fn start_desktop_event_listener(_app_handle: AppHandle) {}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Windows notifications
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod listener {
+    use super::super::names::{on_windows_name_changed, sync_names};
+    use super::super::reconcile;
+    use crate::workspace::follow_virtual_desktop;
+    use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+    use std::time::Duration;
+    use tauri::{AppHandle, Emitter};
+    use winvd::{listen_desktop_events, DesktopEvent};
+
+    /// Быстрое пролистывание столов — применяем только тот, на котором остановились
+    const FOLLOW_DEBOUNCE_MS: u64 = 300;
+
+    pub fn start(app_handle: AppHandle) {
+        std::thread::spawn(move || {
+            if let Err(e) = sync_names() {
+                eprintln!("Не удалось синхронизировать имена столов: {}", e);
+            }
+
+            // Столы могли добавить или удалить, пока Kimi не работал
+            match reconcile::check() {
+                Ok(report) if !report.is_empty() => {
+                    let _ = app_handle.emit("virtual-desktops-out-of-sync", report);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Не удалось сверить виртуальные столы: {}", e),
+            }
+
+            let (tx, rx) = channel::<DesktopEvent>();
+
+            // Поток уведомлений живёт, пока жива эта переменная
+            let _notifications = match listen_desktop_events(tx) {
+                Ok(thread) => thread,
+                Err(e) => {
+                    eprintln!("Не удалось подписаться на события виртуальных столов: {:?}", e);
+                    return;
+                }
+            };
+
+            event_loop(&app_handle, rx);
+        });
+    }
+
+    fn event_loop(app_handle: &AppHandle, rx: Receiver<DesktopEvent>) {
+        let mut pending_switch: Option<usize> = None;
+
+        loop {
+            let event = if pending_switch.is_some() {
+                match rx.recv_timeout(Duration::from_millis(FOLLOW_DEBOUNCE_MS)) {
+                    Ok(event) => Some(event),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            } else {
+                match rx.recv() {
+                    Ok(event) => Some(event),
+                    Err(_) => return,
+                }
+            };
+
+            match event {
+                Some(DesktopEvent::DesktopChanged { new, .. }) => {
+                    pending_switch = new.get_index().ok().map(|i| i as usize);
+                }
+                Some(DesktopEvent::DesktopNameChanged(desktop, name)) => {
+                    let Ok(index) = desktop.get_index() else {
+                        continue;
+                    };
+
+                    if let Ok(Some(id)) = on_windows_name_changed(index as usize, &name) {
+                        let _ = app_handle.emit("desktop-renamed", id);
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    if let Some(index) = pending_switch.take() {
+                        match follow_virtual_desktop(index) {
+                            Ok(Some(id)) => {
+                                let _ = app_handle.emit("virtual-desktop-followed", id);
+                            }
+                            Ok(None) => {}
+                            Err(e) => eprintln!("Не удалось применить стол Kimi: {}", e),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
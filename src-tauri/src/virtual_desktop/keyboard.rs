@@ -1,112 +1,37 @@
-//! Управление виртуальными столами через эмуляцию клавиатуры
-
-use std::thread;
-use std::time::Duration;
-
-// ─────────────────────────────────────────────────────────────────────────────
-// Tauri Commands
-// ─────────────────────────────────────────────────────────────────────────────
-
-#[tauri::command]
-pub async fn create_virtual_desktop() -> Result<usize, String> {
-    platform::create_desktop()
-}
-
-#[tauri::command]
-pub async fn remove_current_virtual_desktop() -> Result<(), String> {
-    platform::remove_current_desktop()
-}
-
-#[tauri::command]
-pub async fn switch_desktop_left() -> Result<usize, String> {
-    platform::switch_left()
-}
-
-#[tauri::command]
-pub async fn switch_desktop_right() -> Result<usize, String> {
-    platform::switch_right()
-}
-
-// ─────────────────────────────────────────────────────────────────────────────
-// Public helper
-// ─────────────────────────────────────────────────────────────────────────────
+//! Управление виртуальными столами через эмуляцию клавиатуры, когда API недоступно
 
 #[cfg(windows)]
-pub fn ensure_virtual_desktops_exist(required: usize) -> Result<(), String> {
-    platform::ensure_desktops_exist(required)
-}
-
-#[cfg(not(windows))]
-pub fn ensure_virtual_desktops_exist(_required: usize) -> Result<(), String> {
-    Err("Только для Windows".to_string())
-}
-
-// ─────────────────────────────────────────────────────────────────────────────
-// Platform-specific implementation
-// ─────────────────────────────────────────────────────────────────────────────
+pub use platform::*;
 
 #[cfg(windows)]
 mod platform {
-    use super::*;
+    use std::thread;
+    use std::time::Duration;
     use windows::Win32::UI::Input::KeyboardAndMouse::{
         keybd_event, KEYBD_EVENT_FLAGS, VIRTUAL_KEY, VK_CONTROL, VK_D, VK_F4, VK_LEFT, VK_LWIN, VK_RIGHT,
     };
-    use winvd::{get_current_desktop, get_desktop_count};
-
-    pub fn create_desktop() -> Result<usize, String> {
-        let before = get_desktop_count().map_err(|e| format!("{:?}", e))? as usize;
 
+    pub fn create_desktop() -> Result<(), String> {
         send_combo(&[(VK_LWIN, false), (VK_CONTROL, false), (VK_D, false)]);
         thread::sleep(Duration::from_millis(500));
-
-        let after = get_desktop_count().map_err(|e| format!("{:?}", e))? as usize;
-
-        if after > before {
-            Ok(after - 1)
-        } else {
-            Err("Не удалось создать виртуальный стол".to_string())
-        }
+        Ok(())
     }
 
     pub fn remove_current_desktop() -> Result<(), String> {
-        let count = get_desktop_count().map_err(|e| format!("{:?}", e))?;
-
-        if count <= 1 {
-            return Err("Нельзя удалить последний виртуальный стол".to_string());
-        }
-
         send_combo(&[(VK_LWIN, false), (VK_CONTROL, false), (VK_F4, false)]);
         thread::sleep(Duration::from_millis(500));
-
         Ok(())
     }
 
-    pub fn switch_left() -> Result<usize, String> {
+    pub fn switch_left() -> Result<(), String> {
         send_combo(&[(VK_LWIN, false), (VK_CONTROL, false), (VK_LEFT, true)]);
         thread::sleep(Duration::from_millis(300));
-        get_current_desktop().map_err(|e| format!("{:?}", e))
+        Ok(())
     }
 
-    pub fn switch_right() -> Result<usize, String> {
+    pub fn switch_right() -> Result<(), String> {
         send_combo(&[(VK_LWIN, false), (VK_CONTROL, false), (VK_RIGHT, true)]);
         thread::sleep(Duration::from_millis(300));
-        get_current_desktop().map_err(|e| format!("{:?}", e))
-    }
-
-    pub fn ensure_desktops_exist(required: usize) -> Result<(), String> {
-        let mut current = get_desktop_count().map_err(|e| format!("{:?}", e))? as usize;
-
-        while current < required {
-            send_combo(&[(VK_LWIN, false), (VK_CONTROL, false), (VK_D, false)]);
-            thread::sleep(Duration::from_millis(500));
-
-            let new = get_desktop_count().map_err(|e| format!("{:?}", e))? as usize;
-            if new == current {
-                return Err(format!("Не удалось создать стол. Нужно: {}, есть: {}", required, current));
-            }
-            current = new;
-        }
-
         Ok(())
     }
 
@@ -128,26 +53,3 @@ mod platform {
         }
     }
 }
-
-#[cfg(not(windows))]
-mod platform {
-    pub fn create_desktop() -> Result<usize, String> {
-        Err("Только для Windows".to_string())
-    }
-
-    pub fn remove_current_desktop() -> Result<(), String> {
-        Err("Только для Windows".to_string())
-    }
-
-    pub fn switch_left() -> Result<usize, String> {
-        Err("Только для Windows".to_string())
-    }
-
-    pub fn switch_right() -> Result<usize, String> {
-        Err("Только для Windows".to_string())
-    }
-
-    pub fn ensure_desktops_exist(_: usize) -> Result<(), String> {
-        Err("Только для Windows".to_string())
-    }
-}
@@ -0,0 +1,288 @@
+//! Создание, удаление и перемещение между виртуальными столами
+
+use serde::Serialize;
+
+use super::api::{current_desktop_index, desktop_count, switch_to_desktop};
+use super::evacuate::{evacuate_windows, previous_index, EvacuationReport, EvacuationTarget};
+use crate::config::{load_config, KimiConfig};
+use crate::workspace::virtual_desktop_index_for;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlMethod {
+    Api,
+    Keyboard,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VirtualDesktopCapabilities {
+    pub method: ControlMethod,
+    pub desktop_count: Option<usize>,
+    pub can_remove_by_index: bool,
+    pub can_rename: bool,
+    /// Почему API недоступно, если используется клавиатура
+    pub api_error: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tauri Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_virtual_desktop_capabilities() -> Result<VirtualDesktopCapabilities, String> {
+    let probe = desktop_count();
+    let api_available = probe.is_ok();
+
+    Ok(VirtualDesktopCapabilities {
+        method: if api_available { ControlMethod::Api } else { ControlMethod::Keyboard },
+        desktop_count: probe.as_ref().ok().copied(),
+        can_remove_by_index: api_available,
+        can_rename: api_available,
+        api_error: probe.err(),
+    })
+}
+
+/// Индекс нового стола; `None` — стол создан клавиатурой и позиция неизвестна
+#[tauri::command]
+pub async fn create_virtual_desktop() -> Result<Option<usize>, String> {
+    create_desktop()
+}
+
+/// Без `evacuation` окна переносит сама Windows (и работает клавиатурный вариант)
+#[tauri::command]
+pub async fn remove_current_virtual_desktop(
+    evacuation: Option<EvacuationTarget>,
+) -> Result<EvacuationReport, String> {
+    match (method(), evacuation) {
+        (ControlMethod::Api, Some(evacuation)) => {
+            let current = current_desktop_index()?;
+            remove_with_evacuation(&load_config(), current, &evacuation)
+        }
+        (ControlMethod::Api, None) => {
+            let current = current_desktop_index()?;
+            remove_desktop(current, None)?;
+            Ok(EvacuationReport {
+                removed_index: Some(current),
+                ..Default::default()
+            })
+        }
+        (ControlMethod::Keyboard, Some(_)) => {
+            Err("Перенос окон требует API виртуальных столов".to_string())
+        }
+        (ControlMethod::Keyboard, None) => {
+            platform::keyboard_remove_current()?;
+            Ok(EvacuationReport::default())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn remove_virtual_desktop(index: usize, fallback_index: Option<usize>) -> Result<(), String> {
+    remove_desktop(index, fallback_index)
+}
+
+/// Индекс стола после переключения; `None` — переключали клавиатурой
+#[tauri::command]
+pub async fn switch_desktop_left() -> Result<Option<usize>, String> {
+    match method() {
+        ControlMethod::Api => {
+            let current = current_desktop_index()?;
+            switch_relative(current.saturating_sub(1))
+        }
+        ControlMethod::Keyboard => platform::keyboard_switch(false),
+    }
+}
+
+#[tauri::command]
+pub async fn switch_desktop_right() -> Result<Option<usize>, String> {
+    match method() {
+        ControlMethod::Api => {
+            let current = current_desktop_index()?;
+            let last = desktop_count()?.saturating_sub(1);
+            switch_relative((current + 1).min(last))
+        }
+        ControlMethod::Keyboard => platform::keyboard_switch(true),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn method() -> ControlMethod {
+    if desktop_count().is_ok() {
+        ControlMethod::Api
+    } else {
+        ControlMethod::Keyboard
+    }
+}
+
+pub fn create_desktop() -> Result<Option<usize>, String> {
+    match method() {
+        ControlMethod::Api => platform::create().map(Some),
+        ControlMethod::Keyboard => platform::keyboard_create(),
+    }
+}
+
+/// Удаление по индексу — только через API: клавиатурой можно закрыть лишь текущий стол
+pub fn remove_desktop(index: usize, fallback_index: Option<usize>) -> Result<(), String> {
+    if method() == ControlMethod::Keyboard {
+        return Err("Удаление стола по индексу требует API виртуальных столов".to_string());
+    }
+
+    let count = desktop_count()?;
+
+    if count <= 1 {
+        return Err("Нельзя удалить последний виртуальный стол".to_string());
+    }
+
+    if index >= count {
+        return Err(format!("Виртуальный стол {} не существует", index));
+    }
+
+    let fallback = fallback_index.unwrap_or_else(|| previous_index(index));
+
+    if fallback == index || fallback >= count {
+        return Err("Некорректный стол для переноса окон".to_string());
+    }
+
+    platform::remove(index, fallback)
+}
+
+/// Переносит окна стола по выбору пользователя и удаляет стол
+pub fn remove_with_evacuation(
+    config: &KimiConfig,
+    index: usize,
+    evacuation: &EvacuationTarget,
+) -> Result<EvacuationReport, String> {
+    if method() == ControlMethod::Keyboard {
+        return Err("Перенос окон требует API виртуальных столов".to_string());
+    }
+
+    let count = desktop_count()?;
+
+    if count <= 1 {
+        return Err("Нельзя удалить последний виртуальный стол".to_string());
+    }
+
+    if index >= count {
+        return Err(format!("Виртуальный стол {} не существует", index));
+    }
+
+    let target = match evacuation {
+        EvacuationTarget::KimiDesktop { desktop_id } => Some(
+            virtual_desktop_index_for(config, *desktop_id)
+                .filter(|&target| target < count)
+                .ok_or("Стол для переноса окон не найден")?,
+        ),
+        EvacuationTarget::Previous => Some(previous_index(index)),
+        EvacuationTarget::Close => None,
+    };
+
+    if target == Some(index) {
+        return Err("Некорректный стол для переноса окон".to_string());
+    }
+
+    let report = evacuate_windows(index, target)?;
+    remove_desktop(index, Some(target.unwrap_or_else(|| previous_index(index))))?;
+
+    Ok(report)
+}
+
+/// Без API количество столов неизвестно — создавать их вслепую нельзя
+pub fn ensure_virtual_desktops_exist(required: usize) -> Result<(), String> {
+    if method() == ControlMethod::Keyboard {
+        return Err("Без API виртуальных столов нельзя проверить, что нужный стол существует".to_string());
+    }
+
+    let mut current = desktop_count()?;
+
+    while current < required {
+        create_desktop()?;
+
+        let new = desktop_count()?;
+        if new == current {
+            return Err(format!("Не удалось создать стол. Нужно: {}, есть: {}", required, current));
+        }
+        current = new;
+    }
+
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn switch_relative(target: usize) -> Result<Option<usize>, String> {
+    switch_to_desktop(target)?;
+    Ok(Some(target))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use super::super::api::desktop_count;
+    use super::super::keyboard;
+
+    pub fn create() -> Result<usize, String> {
+        winvd::create_desktop()
+            .and_then(|d| d.get_index())
+            .map(|i| i as usize)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn remove(index: usize, fallback: usize) -> Result<(), String> {
+        winvd::remove_desktop(index as u32, fallback as u32).map_err(|e| format!("{:?}", e))
+    }
+
+    /// Клавиатурный путь работает, когда API недоступно, поэтому ни индекс
+    /// нового стола, ни факт его создания проверить нечем — верим комбинации
+    pub fn keyboard_create() -> Result<Option<usize>, String> {
+        keyboard::create_desktop()?;
+        Ok(None)
+    }
+
+    pub fn keyboard_remove_current() -> Result<(), String> {
+        if matches!(desktop_count(), Ok(count) if count <= 1) {
+            return Err("Нельзя удалить последний виртуальный стол".to_string());
+        }
+
+        keyboard::remove_current_desktop()
+    }
+
+    pub fn keyboard_switch(right: bool) -> Result<Option<usize>, String> {
+        if right {
+            keyboard::switch_right()?;
+        } else {
+            keyboard::switch_left()?;
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn create() -> Result<usize, String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn remove(_: usize, _: usize) -> Result<(), String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn keyboard_create() -> Result<Option<usize>, String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn keyboard_remove_current() -> Result<(), String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn keyboard_switch(_: bool) -> Result<Option<usize>, String> {
+        Err("Только для Windows".to_string())
+    }
+}
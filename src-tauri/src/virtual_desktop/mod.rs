@@ -1,6 +1,12 @@
 //! Модуль виртуальных рабочих столов Windows
 
 pub mod api;
-pub mod keyboard;
+pub mod evacuate;
+pub mod events;
+mod keyboard;
+pub mod manage;
+pub mod names;
+pub mod reconcile;
+pub mod search;
 
-pub use keyboard::ensure_virtual_desktops_exist;
+pub use manage::ensure_virtual_desktops_exist;
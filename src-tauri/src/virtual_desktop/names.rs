@@ -0,0 +1,204 @@
+//! Синхронизация имён столов Kimi с именами виртуальных столов Windows
+
+use serde::Serialize;
+
+use crate::config::{
+    load_config, save_config, KimiConfig, NameConflictPolicy, NameSyncSettings, CONFIG_LOCK,
+};
+use crate::workspace::{kimi_desktop_for_virtual_index, virtual_desktop_index_for};
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct NameSyncReport {
+    /// Столы, чьё имя из Kimi записано в Windows
+    pub pushed: Vec<i32>,
+    /// Столы, получившие имя из Windows
+    pub pulled: Vec<i32>,
+    pub conflicts: Vec<NameConflict>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct NameConflict {
+    pub desktop_id: i32,
+    pub kimi_name: String,
+    pub windows_name: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NameResolution {
+    Keep,
+    UseKimi,
+    UseWindows,
+    Conflict,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tauri Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn sync_desktop_names() -> Result<NameSyncReport, String> {
+    sync_names()
+}
+
+#[tauri::command]
+pub async fn get_name_sync_settings() -> Result<NameSyncSettings, String> {
+    Ok(load_config().name_sync)
+}
+
+#[tauri::command]
+pub async fn set_name_sync_settings(settings: NameSyncSettings) -> Result<(), String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    config.name_sync = settings;
+    save_config(&config)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Полная сверка имён всех связанных столов с учётом политики конфликтов
+pub fn sync_names() -> Result<NameSyncReport, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+    let mut report = NameSyncReport::default();
+
+    if !config.name_sync.enabled {
+        return Ok(report);
+    }
+
+    let windows_names = platform::get_names()?;
+    let policy = config.name_sync.conflict_policy;
+    let ids: Vec<i32> = config.desktops.iter().map(|d| d.id).collect();
+
+    for id in ids {
+        let Some(index) = virtual_desktop_index_for(&config, id) else {
+            continue;
+        };
+        let Some(windows_name) = windows_names.get(index) else {
+            continue;
+        };
+        let Some(desktop) = config.desktops.iter_mut().find(|d| d.id == id) else {
+            continue;
+        };
+
+        match resolve_name(&desktop.name, windows_name, policy) {
+            NameResolution::Keep => {}
+            NameResolution::UseKimi => {
+                platform::set_name(index, &desktop.name)?;
+                report.pushed.push(id);
+            }
+            NameResolution::UseWindows => {
+                desktop.name = windows_name.clone();
+                report.pulled.push(id);
+            }
+            NameResolution::Conflict => report.conflicts.push(NameConflict {
+                desktop_id: id,
+                kimi_name: desktop.name.clone(),
+                windows_name: windows_name.clone(),
+            }),
+        }
+    }
+
+    if !report.pulled.is_empty() {
+        save_config(&config)?;
+    }
+
+    Ok(report)
+}
+
+/// Переименование в Kimi — записываем имя и в связанный стол Windows
+pub fn push_name_to_windows(config: &KimiConfig, kimi_desktop_id: i32, name: &str) -> Result<(), String> {
+    if !config.name_sync.enabled {
+        return Ok(());
+    }
+
+    let Some(index) = virtual_desktop_index_for(config, kimi_desktop_id) else {
+        return Ok(());
+    };
+
+    // Виртуальный стол ещё не создан — имя запишется при следующей синхронизации
+    if index >= platform::get_names()?.len() {
+        return Ok(());
+    }
+
+    platform::set_name(index, name)
+}
+
+/// Имя изменили в Task View: последнее действие пользователя, поэтому
+/// принимаем его без политики конфликтов
+pub fn on_windows_name_changed(index: usize, name: &str) -> Result<Option<i32>, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    if !config.name_sync.enabled || name.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let Some(id) = kimi_desktop_for_virtual_index(&config, index) else {
+        return Ok(None);
+    };
+    let Some(desktop) = config.desktops.iter_mut().find(|d| d.id == id) else {
+        return Ok(None);
+    };
+
+    if desktop.name == name {
+        return Ok(None);
+    }
+
+    desktop.name = name.to_string();
+    save_config(&config)?;
+
+    Ok(Some(id))
+}
+
+pub fn resolve_name(kimi: &str, windows: &str, policy: NameConflictPolicy) -> NameResolution {
+    if kimi == windows {
+        return NameResolution::Keep;
+    }
+
+    // У стола Windows нет своего имени — конфликта нет
+    if windows.trim().is_empty() {
+        return NameResolution::UseKimi;
+    }
+
+    match policy {
+        NameConflictPolicy::PreferKimi => NameResolution::UseKimi,
+        NameConflictPolicy::PreferWindows => NameResolution::UseWindows,
+        NameConflictPolicy::Skip => NameResolution::Conflict,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    pub fn get_names() -> Result<Vec<String>, String> {
+        let desktops = winvd::get_desktops().map_err(|e| format!("{:?}", e))?;
+
+        desktops
+            .iter()
+            .map(|d| d.get_name().map_err(|e| format!("{:?}", e)))
+            .collect()
+    }
+
+    pub fn set_name(index: usize, name: &str) -> Result<(), String> {
+        winvd::get_desktop(index as u32)
+            .set_name(name)
+            .map_err(|e| format!("{:?}", e))
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn get_names() -> Result<Vec<String>, String> {
+        Err("Только для Windows".to_string())
+    }
+
+    pub fn set_name(_: usize, _: &str) -> Result<(), String> {
+        Err("Только для Windows".to_string())
+    }
+}
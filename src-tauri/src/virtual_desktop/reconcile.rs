@@ -0,0 +1,179 @@
+//! Сверка столов Kimi с виртуальными столами Windows
+
+use serde::{Deserialize, Serialize};
+
+use super::api::list_desktops;
+use super::manage::remove_desktop;
+use crate::config::{load_config, save_config, KimiConfig, CONFIG_LOCK};
+use crate::desktop::manager::add_desktop;
+use crate::workspace::{mapping_index, normalize_guid, resolve_kimi_desktop};
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ReconcileReport {
+    /// Связи со столами, удалёнными в Windows
+    pub stale_mappings: Vec<StaleMapping>,
+    /// Виртуальные столы, которым не соответствует ни один стол Kimi
+    pub unmanaged: Vec<UnmanagedDesktop>,
+    /// Связанные столы, порядок которых в Windows отличается от порядка в Kimi
+    pub reordered: Vec<ReorderedDesktop>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StaleMapping {
+    pub kimi_desktop_id: i32,
+    pub virtual_desktop_id: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UnmanagedDesktop {
+    pub index: usize,
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReorderedDesktop {
+    pub kimi_desktop_id: i32,
+    pub virtual_desktop_id: String,
+    /// Позиция в списке Kimi
+    pub position: usize,
+    /// Текущий индекс в Windows
+    pub index: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReconcileAction {
+    /// Создать стол Kimi с именем виртуального стола и связать их
+    CreateKimiDesktop { virtual_desktop_id: String },
+    /// Забыть связь — стол Kimi вернётся к сопоставлению по позиции
+    DropMapping { kimi_desktop_id: i32 },
+    /// Удалить лишний виртуальный стол, окна уйдут на соседний
+    RemoveVirtualDesktop { virtual_desktop_id: String },
+}
+
+impl ReconcileReport {
+    pub fn is_empty(&self) -> bool {
+        self.stale_mappings.is_empty() && self.unmanaged.is_empty() && self.reordered.is_empty()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tauri Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn check_virtual_desktops() -> Result<ReconcileReport, String> {
+    check()
+}
+
+/// Выполняет выбранные действия и возвращает свежий отчёт.
+///
+/// Конфиг сохраняется после каждого действия: у действий есть последствия
+/// вне конфига (созданные папки, удалённые столы), и при ошибке на середине
+/// уже выполненное не должно потеряться. Оставшиеся действия не выполняются
+#[tauri::command]
+pub async fn apply_reconcile_actions(actions: Vec<ReconcileAction>) -> Result<ReconcileReport, String> {
+    {
+        let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+        let mut config = load_config();
+
+        for action in &actions {
+            apply_action(&mut config, action)?;
+            save_config(&config)?;
+        }
+    }
+
+    check()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn check() -> Result<ReconcileReport, String> {
+    let desktops = list_desktops()?;
+    Ok(reconcile(&load_config(), &desktops))
+}
+
+/// Чистая сверка: `desktops` — пары (GUID, имя) в порядке индексов Windows
+pub fn reconcile(config: &KimiConfig, desktops: &[(String, String)]) -> ReconcileReport {
+    let ids: Vec<String> = desktops.iter().map(|(id, _)| id.clone()).collect();
+    let mut report = ReconcileReport::default();
+
+    for (position, desktop) in config.desktops.iter().enumerate() {
+        let Some(value) = config.virtual_desktop_mapping.get(&desktop.id) else {
+            continue;
+        };
+
+        match mapping_index(value, &ids) {
+            None => report.stale_mappings.push(StaleMapping {
+                kimi_desktop_id: desktop.id,
+                virtual_desktop_id: value.clone(),
+            }),
+            Some(index) if index != position => report.reordered.push(ReorderedDesktop {
+                kimi_desktop_id: desktop.id,
+                virtual_desktop_id: ids[index].clone(),
+                position,
+                index,
+            }),
+            Some(_) => {}
+        }
+    }
+
+    report.unmanaged = desktops
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| resolve_kimi_desktop(config, *index, &ids).is_none())
+        .map(|(index, (id, name))| UnmanagedDesktop {
+            index,
+            id: id.clone(),
+            name: name.clone(),
+        })
+        .collect();
+
+    report
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Internal helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn apply_action(config: &mut KimiConfig, action: &ReconcileAction) -> Result<(), String> {
+    match action {
+        ReconcileAction::CreateKimiDesktop { virtual_desktop_id } => {
+            let guid = normalize_guid(virtual_desktop_id);
+            let (_, name) = find_desktop(&guid)?;
+
+            let desktop = add_desktop(config, Some(name))?;
+            config.virtual_desktop_mapping.insert(desktop.id, guid);
+        }
+        ReconcileAction::DropMapping { kimi_desktop_id } => {
+            config.virtual_desktop_mapping.remove(kimi_desktop_id);
+        }
+        ReconcileAction::RemoveVirtualDesktop { virtual_desktop_id } => {
+            let guid = normalize_guid(virtual_desktop_id);
+            let (index, _) = find_desktop(&guid)?;
+
+            // Столы Kimi не удаляем — только виртуальные, которые им не принадлежат
+            let ids: Vec<String> = list_desktops()?.into_iter().map(|(id, _)| id).collect();
+            if resolve_kimi_desktop(config, index, &ids).is_some() {
+                return Err("Виртуальный стол связан со столом Kimi".to_string());
+            }
+
+            remove_desktop(index, None)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Индекс ищем заново перед каждым действием: удаление сдвигает остальные столы
+fn find_desktop(guid: &str) -> Result<(usize, String), String> {
+    list_desktops()?
+        .into_iter()
+        .enumerate()
+        .find(|(_, (id, _))| id == guid)
+        .map(|(index, (_, name))| (index, name))
+        .ok_or_else(|| "Виртуальный стол Windows не найден".to_string())
+}
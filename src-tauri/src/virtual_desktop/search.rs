@@ -0,0 +1,166 @@
+//! Поиск окон по всем виртуальным столам и переход к найденному окну
+
+use serde::Serialize;
+
+use super::api::{enumerate_windows, get_window_info, switch_to_desktop, virtual_desktop_ids, WindowInfo};
+use crate::config::load_config;
+use crate::workspace::{kimi_desktop_for_virtual_index, perform_switch, resolve_kimi_desktop};
+
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WindowSearchResult {
+    pub window: WindowInfo,
+    pub score: i32,
+    pub kimi_desktop_id: Option<i32>,
+    pub desktop_name: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tauri Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn search_windows(query: String, limit: Option<usize>) -> Result<Vec<WindowSearchResult>, String> {
+    let config = load_config();
+    let ids = virtual_desktop_ids().unwrap_or_default();
+
+    let mut results: Vec<WindowSearchResult> = enumerate_windows()?
+        .into_iter()
+        .filter_map(|window| {
+            let score = score_window(&query, &window)?;
+            let kimi_desktop_id = window
+                .desktop_index
+                .and_then(|i| resolve_kimi_desktop(&config, i, &ids));
+            let desktop_name = kimi_desktop_id
+                .and_then(|id| config.desktops.iter().find(|d| d.id == id))
+                .map(|d| d.name.clone());
+
+            Some(WindowSearchResult {
+                window,
+                score,
+                kimi_desktop_id,
+                desktop_name,
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.window.desktop_index.cmp(&b.window.desktop_index))
+    });
+    results.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+
+    Ok(results)
+}
+
+/// Переключает рабочее пространство, на котором живёт окно, и активирует его
+#[tauri::command]
+pub async fn jump_to_window(hwnd_raw: isize) -> Result<(), String> {
+    let window = get_window_info(hwnd_raw).ok_or("Окно не найдено")?;
+
+    // Закреплённое окно видно на любом столе — переключаться не нужно
+    if !window.is_pinned && !window.is_app_pinned {
+        if let Some(index) = window.desktop_index {
+            let config = load_config();
+
+            match kimi_desktop_for_virtual_index(&config, index) {
+                Some(id) if id != config.active_desktop_id => perform_switch(id)?,
+                Some(_) => {}
+                None => switch_to_desktop(index)?,
+            }
+        }
+    }
+
+    platform::focus_window(hwnd_raw)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Ranking
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Каждое слово запроса должно найтись в заголовке или имени процесса
+pub fn score_window(query: &str, window: &WindowInfo) -> Option<i32> {
+    query.split_whitespace().try_fold(0, |total, term| {
+        let title = fuzzy_score(term, &window.title);
+        let process = fuzzy_score(term, &window.process_name);
+        Some(total + title.max(process)?)
+    })
+}
+
+/// Нечёткое совпадение: символы запроса должны идти в тексте по порядку.
+/// Бонусы за подряд идущие символы, начало слова и точную подстроку.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut q = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for (t, &c) in text.iter().enumerate() {
+        if q == query.len() {
+            break;
+        }
+        if c != query[q] {
+            continue;
+        }
+
+        score += 1;
+        if t > 0 && prev_match == Some(t - 1) {
+            score += 5;
+        }
+        if t == 0 || !text[t - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        prev_match = Some(t);
+        q += 1;
+    }
+
+    if q < query.len() {
+        return None;
+    }
+
+    let needle: String = query.iter().collect();
+    let haystack: String = text.iter().collect();
+    if haystack.contains(&needle) {
+        score += 20;
+    }
+
+    Some(score)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform-specific implementation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(windows)]
+mod platform {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{IsIconic, SetForegroundWindow, ShowWindow, SW_RESTORE};
+
+    pub fn focus_window(hwnd: isize) -> Result<(), String> {
+        let hwnd = HWND(hwnd as *mut _);
+
+        unsafe {
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+
+            if SetForegroundWindow(hwnd).as_bool() {
+                Ok(())
+            } else {
+                Err("Не удалось активировать окно".to_string())
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn focus_window(_: isize) -> Result<(), String> {
+        Err("Только для Windows".to_string())
+    }
+}
@@ -4,16 +4,22 @@ use std::collections::HashMap;
 use std::thread;
 use std::time::Duration;
 
-use crate::config::{get_desktop_folder_path, load_config, save_config, CONFIG_LOCK};
+use crate::config::{get_desktop_folder_path, load_config, save_config, KimiConfig, CONFIG_LOCK};
 use crate::desktop::icons::{
     get_current_icon_positions, load_icon_positions, restore_icon_positions, save_icon_positions,
 };
+use crate::desktop::manager::check_can_delete;
 use crate::desktop::set_desktop_path;
+use crate::virtual_desktop::api::virtual_desktop_ids;
+use crate::virtual_desktop::evacuate::{EvacuationReport, EvacuationTarget};
+use crate::virtual_desktop::manage::remove_with_evacuation;
 
 #[cfg(windows)]
 use crate::virtual_desktop::ensure_virtual_desktops_exist;
 #[cfg(windows)]
-use winvd::go_to_desktop;
+use crate::virtual_desktop::manage::create_desktop;
+#[cfg(windows)]
+use winvd::switch_desktop;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Tauri Commands
@@ -21,48 +27,14 @@ use winvd::go_to_desktop;
 
 #[tauri::command]
 pub async fn switch_workspace(kimi_desktop_id: i32) -> Result<(), String> {
-    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
-    let mut config = load_config();
-
-    // Сохраняем иконки текущего стола
-    save_icons_for_current(&config, kimi_desktop_id);
-
-    // Находим индекс целевого стола
-    let kimi_index = config
-        .desktops
-        .iter()
-        .position(|d| d.id == kimi_desktop_id)
-        .ok_or("Рабочий стол Kimi не найден")?;
-
-    // Переключаем путь
-    let desktop_path = get_desktop_folder_path(kimi_desktop_id);
-    if !desktop_path.exists() {
-        return Err("Папка рабочего стола не существует".to_string());
-    }
-
-    set_desktop_path(&desktop_path.to_string_lossy())?;
-
-    // Переключаем виртуальный стол Windows
-    #[cfg(windows)]
-    {
-        ensure_virtual_desktops_exist(kimi_index + 1)?;
-        let _ = go_to_desktop(kimi_index);
-    }
-
-    config.active_desktop_id = kimi_desktop_id;
-    save_config(&config)?;
-
-    // Восстанавливаем иконки нового стола
-    thread::sleep(Duration::from_millis(300));
-    restore_icons_for_desktop(&desktop_path);
-
-    Ok(())
+    perform_switch(kimi_desktop_id)
 }
 
+/// Связывает стол Kimi с виртуальным столом Windows по его GUID
 #[tauri::command]
 pub async fn link_to_virtual_desktop(
     kimi_desktop_id: i32,
-    virtual_desktop_index: String,
+    virtual_desktop_id: String,
 ) -> Result<(), String> {
     let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
     let mut config = load_config();
@@ -71,7 +43,14 @@ pub async fn link_to_virtual_desktop(
         return Err("Рабочий стол Kimi не найден".to_string());
     }
 
-    config.virtual_desktop_mapping.insert(kimi_desktop_id, virtual_desktop_index);
+    let virtual_desktop_id = normalize_guid(&virtual_desktop_id);
+
+    #[cfg(windows)]
+    if !virtual_desktop_ids()?.contains(&virtual_desktop_id) {
+        return Err("Виртуальный стол Windows не найден".to_string());
+    }
+
+    config.virtual_desktop_mapping.insert(kimi_desktop_id, virtual_desktop_id);
     save_config(&config)?;
 
     Ok(())
@@ -94,11 +73,241 @@ pub async fn get_workspace_mappings() -> Result<HashMap<i32, String>, String> {
     Ok(config.virtual_desktop_mapping)
 }
 
+/// Удаляет стол Kimi вместе со связанным виртуальным столом, окна которого
+/// переносятся по выбору пользователя. Папка стола остаётся на диске
+#[tauri::command]
+pub async fn delete_workspace(
+    kimi_desktop_id: i32,
+    evacuation: EvacuationTarget,
+) -> Result<EvacuationReport, String> {
+    // Перенос окон ждёт их закрытия до нескольких секунд — конфиг на это время
+    // не блокируем, чтобы не останавливать фоновые потоки
+    let config = load_config();
+    check_can_delete(&config, kimi_desktop_id)?;
+
+    let ids = virtual_desktop_ids().unwrap_or_default();
+
+    // Удаляем виртуальный стол, только если он действительно принадлежит этому столу Kimi
+    let report = match resolve_virtual_index(&config, kimi_desktop_id, &ids) {
+        Some(index) if resolve_kimi_desktop(&config, index, &ids) == Some(kimi_desktop_id) => {
+            remove_with_evacuation(&config, index, &evacuation)?
+        }
+        _ => EvacuationReport::default(),
+    };
+
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    config.desktops.retain(|d| d.id != kimi_desktop_id);
+    config.virtual_desktop_mapping.remove(&kimi_desktop_id);
+    save_config(&config)?;
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn get_follow_virtual_desktops() -> Result<bool, String> {
+    Ok(load_config().follow_virtual_desktops)
+}
+
+#[tauri::command]
+pub async fn set_follow_virtual_desktops(enabled: bool) -> Result<(), String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    config.follow_virtual_desktops = enabled;
+    save_config(&config)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Общий путь переключения: используется командой и фоновой автоматизацией
+pub fn perform_switch(kimi_desktop_id: i32) -> Result<(), String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    switch_locked(&mut config, kimi_desktop_id, true)
+}
+
+/// Пользователь сам переключил виртуальный стол (Win+Ctrl+стрелка, Task View) —
+/// подтягиваем файлы и иконки связанного стола Kimi, не трогая виртуальный стол
+pub fn follow_virtual_desktop(index: usize) -> Result<Option<i32>, String> {
+    let _lock = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config();
+
+    if !config.follow_virtual_desktops {
+        return Ok(None);
+    }
+
+    let Some(kimi_desktop_id) = kimi_desktop_for_virtual_index(&config, index) else {
+        return Ok(None);
+    };
+
+    // Переключение, сделанное самим Kimi, сюда тоже приходит — оно уже применено
+    if kimi_desktop_id == config.active_desktop_id {
+        return Ok(None);
+    }
+
+    switch_locked(&mut config, kimi_desktop_id, false)?;
+    Ok(Some(kimi_desktop_id))
+}
+
+/// Индекс виртуального стола Windows, связанного со столом Kimi
+pub fn virtual_desktop_index_for(config: &KimiConfig, kimi_desktop_id: i32) -> Option<usize> {
+    resolve_virtual_index(config, kimi_desktop_id, &virtual_desktop_ids().unwrap_or_default())
+}
+
+/// Обратное соответствие: стол Kimi, связанный с виртуальным столом Windows
+pub fn kimi_desktop_for_virtual_index(config: &KimiConfig, index: usize) -> Option<i32> {
+    resolve_kimi_desktop(config, index, &virtual_desktop_ids().unwrap_or_default())
+}
+
+/// Связь из `virtual_desktop_mapping` главнее позиции стола в списке Kimi:
+/// GUID переживает перестановку виртуальных столов. Без связи (или если
+/// связанный стол удалён) — индекс совпадает с позицией в списке, если
+/// этот виртуальный стол не занят связью другого стола Kimi
+pub fn resolve_virtual_index(config: &KimiConfig, kimi_desktop_id: i32, ids: &[String]) -> Option<usize> {
+    let position = config.desktops.iter().position(|d| d.id == kimi_desktop_id)?;
+
+    if let Some(index) = config
+        .virtual_desktop_mapping
+        .get(&kimi_desktop_id)
+        .and_then(|value| mapping_index(value, ids))
+    {
+        return Some(index);
+    }
+
+    let claimed = config
+        .virtual_desktop_mapping
+        .iter()
+        .filter(|(id, _)| **id != kimi_desktop_id && config.desktops.iter().any(|d| d.id == **id))
+        .any(|(_, value)| mapping_index(value, ids) == Some(position));
+
+    (!claimed).then_some(position)
+}
+
+/// Как `resolve_virtual_index`, но позицию, занятую связью другого стола,
+/// заменяет первым свободным виртуальным столом и запоминает связь с ним.
+/// `None` — свободного стола нет
+pub fn claim_virtual_index(config: &mut KimiConfig, kimi_desktop_id: i32, ids: &[String]) -> Option<usize> {
+    if let Some(index) = resolve_virtual_index(config, kimi_desktop_id, ids) {
+        return Some(index);
+    }
+
+    if !config.desktops.iter().any(|d| d.id == kimi_desktop_id) {
+        return None;
+    }
+
+    let index = (0..ids.len()).find(|&index| resolve_kimi_desktop(config, index, ids).is_none())?;
+    config.virtual_desktop_mapping.insert(kimi_desktop_id, ids[index].clone());
+
+    Some(index)
+}
+
+/// Обратная сторона `resolve_virtual_index` для списка GUID текущих столов
+pub fn resolve_kimi_desktop(config: &KimiConfig, index: usize, ids: &[String]) -> Option<i32> {
+    let linked = config
+        .virtual_desktop_mapping
+        .iter()
+        .filter(|(id, _)| config.desktops.iter().any(|d| d.id == **id))
+        .find(|(_, value)| mapping_index(value, ids) == Some(index))
+        .map(|(id, _)| *id);
+
+    if linked.is_some() {
+        return linked;
+    }
+
+    // По позиции — только если у стола на этой позиции нет собственной связи
+    config
+        .desktops
+        .get(index)
+        .filter(|d| {
+            config
+                .virtual_desktop_mapping
+                .get(&d.id)
+                .and_then(|value| mapping_index(value, ids))
+                .is_none()
+        })
+        .map(|d| d.id)
+}
+
+/// GUID в виде `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX`, без фигурных скобок
+pub fn normalize_guid(value: &str) -> String {
+    value.trim().trim_start_matches('{').trim_end_matches('}').to_uppercase()
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Internal helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
-fn save_icons_for_current(config: &crate::config::KimiConfig, new_id: i32) {
+fn switch_locked(config: &mut KimiConfig, kimi_desktop_id: i32, switch_virtual: bool) -> Result<(), String> {
+    // Сохраняем иконки текущего стола
+    save_icons_for_current(config, kimi_desktop_id);
+
+    if !config.desktops.iter().any(|d| d.id == kimi_desktop_id) {
+        return Err("Рабочий стол Kimi не найден".to_string());
+    }
+
+    // Переключаем путь
+    let desktop_path = get_desktop_folder_path(kimi_desktop_id);
+    if !desktop_path.exists() {
+        return Err("Папка рабочего стола не существует".to_string());
+    }
+
+    set_desktop_path(&desktop_path.to_string_lossy())?;
+
+    // Переключаем виртуальный стол Windows
+    #[cfg(windows)]
+    if switch_virtual {
+        let ids = virtual_desktop_ids().unwrap_or_default();
+        let kimi_index = match claim_virtual_index(config, kimi_desktop_id, &ids) {
+            Some(index) => index,
+            None => create_linked_desktop(config, kimi_desktop_id)?,
+        };
+
+        ensure_virtual_desktops_exist(kimi_index + 1)?;
+        let _ = switch_desktop(kimi_index as u32);
+    }
+    #[cfg(not(windows))]
+    let _ = switch_virtual;
+
+    config.active_desktop_id = kimi_desktop_id;
+    save_config(config)?;
+
+    // Восстанавливаем иконки нового стола
+    thread::sleep(Duration::from_millis(300));
+    restore_icons_for_desktop(&desktop_path);
+
+    Ok(())
+}
+
+/// Старые конфиги хранят в связи номер стола, новые — его GUID.
+/// `None` — связанного стола больше нет
+pub fn mapping_index(value: &str, ids: &[String]) -> Option<usize> {
+    if let Ok(index) = value.trim().parse::<usize>() {
+        return (index < ids.len()).then_some(index);
+    }
+
+    let guid = normalize_guid(value);
+    ids.iter().position(|id| *id == guid)
+}
+
+/// Свободных виртуальных столов нет — создаём новый и связываем его со столом Kimi
+#[cfg(windows)]
+fn create_linked_desktop(config: &mut KimiConfig, kimi_desktop_id: i32) -> Result<usize, String> {
+    let index = create_desktop()?.ok_or("Не удалось создать виртуальный стол для стола Kimi")?;
+    let id = virtual_desktop_ids()?
+        .get(index)
+        .cloned()
+        .ok_or("Виртуальный стол Windows не найден")?;
+
+    config.virtual_desktop_mapping.insert(kimi_desktop_id, id);
+    Ok(index)
+}
+
+fn save_icons_for_current(config: &KimiConfig, new_id: i32) {
     let old_id = config.active_desktop_id;
     
     if old_id <= 0 || old_id == new_id {
@@ -114,10 +323,82 @@ fn save_icons_for_current(config: &crate::config::KimiConfig, new_id: i32) {
     }
 }
 
-fn restore_icons_for_desktop(desktop_path: &std::path::PathBuf) {
+fn restore_icons_for_desktop(desktop_path: &std::path::Path) {
     let layout = load_icon_positions(desktop_path);
     
     if !layout.icons.is_empty() {
         let _ = restore_icon_positions(&layout, desktop_path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DesktopConfig;
+
+    fn config(ids: &[i32], links: &[(i32, &str)]) -> KimiConfig {
+        KimiConfig {
+            desktops: ids
+                .iter()
+                .map(|&id| DesktopConfig { id, name: format!("Desktop {}", id) })
+                .collect(),
+            virtual_desktop_mapping: links.iter().map(|&(id, guid)| (id, guid.to_string())).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn guids(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("0000000{}-0000-0000-0000-000000000000", i)).collect()
+    }
+
+    #[test]
+    fn unlinked_desktop_uses_its_position() {
+        let config = config(&[1, 2], &[]);
+        assert_eq!(resolve_virtual_index(&config, 2, &guids(2)), Some(1));
+    }
+
+    #[test]
+    fn link_wins_over_position() {
+        let ids = guids(3);
+        let config = config(&[1, 2], &[(1, &ids[2])]);
+
+        assert_eq!(resolve_virtual_index(&config, 1, &ids), Some(2));
+        assert_eq!(resolve_kimi_desktop(&config, 2, &ids), Some(1));
+    }
+
+    #[test]
+    fn claimed_position_does_not_resolve() {
+        let ids = guids(3);
+        // Стол 1 связан со вторым виртуальным столом — позицией стола 2
+        let config = config(&[1, 2], &[(1, &ids[1])]);
+
+        assert_eq!(resolve_virtual_index(&config, 2, &ids), None);
+    }
+
+    #[test]
+    fn claimed_position_falls_back_to_free_desktop_and_links_it() {
+        let ids = guids(3);
+        let mut config = config(&[1, 2], &[(1, &ids[1])]);
+
+        // Виртуальный стол 0 свободен: позицию стола 1 тот не использует
+        assert_eq!(claim_virtual_index(&mut config, 2, &ids), Some(0));
+        assert_eq!(config.virtual_desktop_mapping.get(&2), Some(&ids[0]));
+        assert_eq!(resolve_virtual_index(&config, 2, &ids), Some(0));
+        assert_eq!(resolve_virtual_index(&config, 1, &ids), Some(1));
+    }
+
+    #[test]
+    fn claimed_position_without_free_desktop_needs_a_new_one() {
+        let ids = guids(2);
+        let mut config = config(&[1, 2, 3], &[(1, &ids[1]), (3, &ids[0])]);
+
+        assert_eq!(claim_virtual_index(&mut config, 2, &ids), None);
+        assert!(!config.virtual_desktop_mapping.contains_key(&2));
+    }
+
+    #[test]
+    fn unknown_desktop_is_not_claimed() {
+        let mut config = config(&[1], &[]);
+        assert_eq!(claim_virtual_index(&mut config, 7, &guids(2)), None);
+    }
+}